@@ -1,18 +1,271 @@
-use wgpu_snake_game::{logic::SnakeGameLogic, renderer::SnakeGameRenderer};
+use std::{path::PathBuf, time::Instant};
+
+use anyhow::Context;
+
+use wgpu_snake_game::{input::{self, Action, KeyboardController, KEYBOARD_PLAYERS}, logic::{controller, Controller, GameConfig, GamePhase, Input, PlayerSnake, Replay, ReplayPlayer, SnakeGameLogic}, renderer::SnakeGameRenderer};
 use winit::application::ApplicationHandler;
 
+// who steers a snake: someone at the keyboard, who can hand over to the autopilot, or a bot
+enum Driver {
+    // `player` is the set of keys, see `Action::Turn`
+    Keyboard { player: usize, keyboard: KeyboardController, autopilot: Option<Box<dyn Controller>> },
+    Bot(Box<dyn Controller>),
+}
+
+impl Driver {
+    // `keyboard` for the next person at the keyboard, otherwise a controller name for `controller::by_name`
+    fn from_name(name: &str, keyboard_players: usize) -> anyhow::Result<Self> {
+        if name != "keyboard" {
+            let bot = controller::by_name(name, rand::random()).with_context(|| format!("unknown player: {}, expected keyboard or a bot", name))?;
+            return Ok(Driver::Bot(bot));
+        }
+
+        if keyboard_players >= KEYBOARD_PLAYERS {
+            anyhow::bail!("at most {} players can share the keyboard", KEYBOARD_PLAYERS);
+        }
+        Ok(Driver::Keyboard { player: keyboard_players, keyboard: KeyboardController::default(), autopilot: None })
+    }
+
+    fn controller(&mut self) -> &mut dyn Controller {
+        match self {
+            Driver::Keyboard { autopilot: Some(bot), .. } => bot.as_mut(),
+            Driver::Keyboard { keyboard, .. } => keyboard,
+            Driver::Bot(bot) => bot.as_mut(),
+        }
+    }
+}
+
 pub struct SnakeGame {
     logic: SnakeGameLogic,
+    phase: GamePhase,
+    config: GameConfig,
     renderer: Option<SnakeGameRenderer>,
     error: Option<anyhow::Error>,
+    // one for every snake, in the same order
+    drivers: Vec<Driver>,
+    last_update: Instant,
+    save_path: PathBuf,
+    // the game being recorded and where it goes once it ends
+    recording: Option<(Replay, PathBuf)>,
+    record_path: Option<PathBuf>,
+    playback: Option<ReplayPlayer>,
+    // steers the keyboard players' snakes while switched on, doubling as a demo mode
+    autopilot_name: String,
 }
 
 impl SnakeGame {
-    pub fn new(width: i32, height: i32) -> Self {
-        Self {
-            logic: SnakeGameLogic::new(width, height),
+    pub fn new(config: GameConfig, seed: u64, save_path: PathBuf) -> Self {
+        let mut snake_game = Self {
+            logic: SnakeGameLogic::new(config.clone(), seed),
+            phase: GamePhase::Title,
+            config,
             renderer: None,
             error: None,
+            drivers: Vec::new(),
+            last_update: Instant::now(),
+            save_path,
+            recording: None,
+            record_path: None,
+            playback: None,
+            autopilot_name: "pathfinding".to_string(),
+        };
+        snake_game.fit_drivers();
+        snake_game
+    }
+
+    // who steers each snake, by `Driver::from_name`; must name every snake of the game
+    pub fn set_players(&mut self, names: &[String]) -> anyhow::Result<()> {
+        let mut drivers = Vec::with_capacity(names.len());
+        for name in names {
+            drivers.push(Driver::from_name(name, Self::keyboard_players(&drivers))?);
+        }
+        self.drivers = drivers;
+        self.fit_drivers();
+        Ok(())
+    }
+
+    fn keyboard_players(drivers: &[Driver]) -> usize {
+        drivers.iter().filter(|driver| matches!(driver, Driver::Keyboard { .. })).count()
+    }
+
+    // one driver per snake: extra snakes, like those of a loaded game, go to the keyboard while
+    // there are keys left and to the autopilot after that
+    fn fit_drivers(&mut self) {
+        let snakes = self.logic.snakes().len();
+        self.drivers.truncate(snakes);
+        while self.drivers.len() < snakes {
+            let keyboard_players = Self::keyboard_players(&self.drivers);
+            let name = if keyboard_players < KEYBOARD_PLAYERS { "keyboard" } else { self.autopilot_name.as_str() };
+            match Driver::from_name(name, keyboard_players) {
+                Ok(driver) => self.drivers.push(driver),
+                Err(error) => {
+                    eprintln!("{:?}", error);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn clear_keyboards(&mut self) {
+        for driver in &mut self.drivers {
+            if let Driver::Keyboard { keyboard, .. } = driver {
+                keyboard.clear();
+            }
+        }
+    }
+
+    // hands every keyboard player's snake to the named bot from `controller::by_name`, or back if
+    // that bot is already steering
+    pub fn toggle_autopilot(&mut self, name: &str) -> anyhow::Result<()> {
+        controller::by_name(name, 0).with_context(|| format!("unknown autopilot: {}", name))?;
+        let active = self.autopilot_name == name
+            && self.drivers.iter().any(|driver| matches!(driver, Driver::Keyboard { autopilot: Some(_), .. }));
+        self.autopilot_name = name.to_string();
+
+        for driver in &mut self.drivers {
+            if let Driver::Keyboard { autopilot, .. } = driver {
+                *autopilot = if active { None } else { controller::by_name(name, rand::random()) };
+            }
+        }
+        println!("autopilot {}", if active { "off" } else { "on" });
+
+        if !active {
+            self.phase = self.phase.start();
+            self.clear_keyboards();
+        }
+        Ok(())
+    }
+
+    // records this and every following game to `path`, replacing the previous recording
+    pub fn record(&mut self, path: PathBuf, seed: u64) {
+        self.recording = Some((Replay::new(seed, self.config.clone()), path.clone()));
+        self.record_path = Some(path);
+    }
+
+    // watches a recorded game instead of playing; restarting rewinds it
+    pub fn play_replay(&mut self, replay: Replay) {
+        let mut player = ReplayPlayer::new(replay);
+        self.logic = player.rewind();
+        self.config = player.replay().config.clone();
+        self.playback = Some(player);
+        self.recording = None;
+        self.record_path = None;
+    }
+
+    fn save_recording(&mut self) {
+        let Some((mut replay, path)) = self.recording.take() else {
+            return;
+        };
+        if self.logic.tick() == 0 {
+            return;
+        }
+
+        replay.finish(&self.logic);
+        match replay.save(&path) {
+            Ok(()) => println!("replay saved to {}", path.display()),
+            Err(error) => eprintln!("{:?}", error),
+        }
+    }
+
+    fn finish_playback(&mut self) {
+        let Some(player) = &self.playback else {
+            return;
+        };
+
+        match player.replay().mismatch(&self.logic) {
+            Some(mismatch) => eprintln!("replay desynced: {}", mismatch),
+            None => println!("replay finished"),
+        }
+    }
+
+    // continues a saved game, paused so the player can get ready
+    pub fn load(&mut self) -> anyhow::Result<()> {
+        let logic = SnakeGameLogic::load(&self.save_path)?;
+        println!("loaded {}", self.save_path.display());
+
+        // a loaded game did not start from a seed, so it can be neither recorded nor a replay
+        self.save_recording();
+        self.playback = None;
+
+        self.phase = match logic.outcome() {
+            Some(outcome) => GamePhase::GameOver(outcome),
+            None => GamePhase::Paused,
+        };
+        self.logic = logic;
+        self.fit_drivers();
+        self.clear_keyboards();
+        Ok(())
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        self.logic.save(&self.save_path)?;
+        println!("saved {}", self.save_path.display());
+        Ok(())
+    }
+
+    fn restart(&mut self) {
+        self.save_recording();
+        self.phase = GamePhase::Playing;
+        self.clear_keyboards();
+
+        if let Some(player) = &mut self.playback {
+            self.logic = player.rewind();
+            return;
+        }
+
+        let seed = rand::random();
+        println!("seed: {}", seed);
+
+        self.logic = SnakeGameLogic::new(self.config.clone(), seed);
+        if let Some(path) = &self.record_path {
+            self.recording = Some((Replay::new(seed, self.config.clone()), path.clone()));
+        }
+    }
+
+    fn action(&mut self, action: Action) {
+        match action {
+            Action::Turn { player, direction } => {
+                if !self.phase.is_playing() || self.playback.is_some() {
+                    return;
+                }
+
+                for driver in &mut self.drivers {
+                    if let Driver::Keyboard { player: driver_player, keyboard, autopilot: None } = driver {
+                        if *driver_player == player {
+                            keyboard.press(direction);
+                        }
+                    }
+                }
+            }
+            Action::Confirm => match self.phase {
+                GamePhase::GameOver(_) => self.restart(),
+                phase => self.phase = phase.start(),
+            },
+            Action::TogglePause => self.phase = self.phase.toggle_pause(),
+            Action::Restart => {
+                if self.phase != GamePhase::Title {
+                    self.restart();
+                }
+            }
+            // a failed save or load is reported but leaves the current game running
+            Action::Save => {
+                if let Err(error) = self.save() {
+                    eprintln!("{:?}", error);
+                }
+            }
+            Action::Load => {
+                if let Err(error) = self.load() {
+                    eprintln!("{:?}", error);
+                }
+            }
+            Action::ToggleAutopilot => {
+                if self.playback.is_none() {
+                    let name = self.autopilot_name.clone();
+                    if let Err(error) = self.toggle_autopilot(&name) {
+                        eprintln!("{:?}", error);
+                    }
+                }
+            }
         }
     }
 }
@@ -33,12 +286,12 @@ impl ApplicationHandler for SnakeGame {
         window_id: winit::window::WindowId,
         event: winit::event::WindowEvent,
     ) {
-        if let Err(error) = self.logic.window_event(&event) {
-            self.error = Some(error);
+        if let Some(action) = input::window_event(&event) {
+            self.action(action);
         }
 
         if let Some(renderer) = &mut self.renderer {
-            if let Err(error) = renderer.window_event(&window_id, &event, &self.logic) {
+            if let Err(error) = renderer.window_event(&window_id, &event, &self.logic, self.phase) {
                 self.error = Some(error);
             }
         }
@@ -54,12 +307,58 @@ impl ApplicationHandler for SnakeGame {
     }
 
     fn about_to_wait(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        let now = Instant::now();
+        let elapsed = now - self.last_update;
+        self.last_update = now;
+
+        if self.phase.is_playing() {
+            if let Some(player) = &mut self.playback {
+                player.advance(&mut self.logic, elapsed);
+                if player.is_finished(&self.logic) {
+                    self.finish_playback();
+                    if !self.logic.is_over() {
+                        self.phase = self.phase.toggle_pause();
+                    }
+                }
+            } else {
+                let drivers = &mut self.drivers;
+                let recording = &mut self.recording;
+                self.logic.advance_with(elapsed, |logic| {
+                    let inputs: Vec<Input> = drivers
+                        .iter_mut()
+                        .enumerate()
+                        .filter(|(snake, _)| logic.snakes().get(*snake).is_some_and(PlayerSnake::is_alive))
+                        .filter_map(|(snake, driver)| driver.controller().decide(logic, snake).map(|direction| Input::Turn { snake, direction }))
+                        .collect();
+                    if let Some((replay, _)) = recording.as_mut() {
+                        replay.record(logic.tick() + 1, &inputs);
+                    }
+                    inputs
+                });
+            }
+
+            if let Some(outcome) = self.logic.outcome() {
+                match self.logic.snakes() {
+                    [snake] => println!("{} - score {}, best combo {}", outcome, snake.score().points(), snake.score().best_combo()),
+                    snakes => {
+                        println!("{}", outcome);
+                        for (index, snake) in snakes.iter().enumerate() {
+                            println!("  player {} - score {}, best combo {}", index + 1, snake.score().points(), snake.score().best_combo());
+                        }
+                    }
+                }
+                self.phase = self.phase.finish(outcome);
+                self.save_recording();
+            }
+        }
+
         if let Some(renderer) = &mut self.renderer {
             renderer.request_redraw();
         }
     }
 
     fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.save_recording();
         self.renderer = None;
     }
-}
\ No newline at end of file
+}
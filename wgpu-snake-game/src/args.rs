@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+
+use crate::logic::{Board, Difficulty, EdgePolicy, FoodCount, GameConfig, Layout, Level};
+
+// the command line, shared by the game and the headless tools
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn from_env() -> Self {
+        Self { args: std::env::args().collect() }
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.args.iter().any(|arg| arg == name)
+    }
+
+    pub fn value(&self, name: &str) -> anyhow::Result<Option<&str>> {
+        match self.args.iter().position(|arg| arg == name) {
+            Some(position) => Ok(Some(self.args.get(position + 1).with_context(|| format!("{} expects a value", name))?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn parsed<T: FromStr>(&self, name: &str, what: &str) -> anyhow::Result<Option<T>> {
+        self.value(name)?
+            .map(|value| value.parse().ok().with_context(|| format!("{} expects {}", name, what)))
+            .transpose()
+    }
+
+    // who steers each snake, from `--players`: either a number of people at the keyboard or a comma
+    // separated list such as `keyboard,pathfinding`, where `keyboard` is a person and anything else a bot
+    pub fn players(&self) -> anyhow::Result<Option<Vec<String>>> {
+        let Some(value) = self.value("--players")? else {
+            return Ok(None);
+        };
+
+        let players = match value.parse::<usize>() {
+            Ok(count) => vec!["keyboard".to_string(); count],
+            Err(_) => value.split(',').map(|name| name.trim().to_string()).collect(),
+        };
+        if players.is_empty() {
+            anyhow::bail!("--players expects at least one player");
+        }
+
+        Ok(Some(players))
+    }
+
+    // the rules picked with `--level`, `--size`, `--wrap`, `--layout`, `--difficulty`, `--food`, `--food-per-cells` and `--players`
+    pub fn game_config(&self) -> anyhow::Result<GameConfig> {
+        let mut config = match self.value("--level")? {
+            Some(path) => {
+                let level = Level::load(path)?;
+                println!("level: {}", level.name);
+                level.config
+            }
+            None => GameConfig::default(),
+        };
+
+        if let Some(size) = self.value("--size")? {
+            let (width, height) = size
+                .split_once('x')
+                .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                .with_context(|| format!("--size expects `<width>x<height>`, found {}", size))?;
+            config.board = Board::new(width, height);
+        }
+        if self.flag("--wrap") {
+            config.edge_policy = EdgePolicy::Wrap;
+        }
+        if let Some(name) = self.value("--layout")? {
+            let layout = Layout::from_name(name).with_context(|| format!("unknown layout: {}", name))?;
+            config.obstacles = layout.obstacles(&config.board);
+        }
+        if let Some(name) = self.value("--difficulty")? {
+            let difficulty = Difficulty::from_name(name).with_context(|| format!("unknown difficulty: {}", name))?;
+            config.speed = difficulty.speed_curve();
+        }
+        if let Some(count) = self.parsed("--food", "a number")? {
+            config.food_count = FoodCount::Fixed(count);
+        }
+        if let Some(cells_per_food) = self.parsed("--food-per-cells", "a number")? {
+            config.food_count = FoodCount::Scaled { cells_per_food };
+        }
+        // last, so new spawns can keep clear of the obstacles
+        if let Some(players) = self.players()? {
+            config.set_players(players.len());
+        }
+        config.validate()?;
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Args {
+        Args { args: line.split_whitespace().map(str::to_string).collect() }
+    }
+
+    #[test]
+    fn reads_values_and_reports_bad_ones() {
+        let args = args("snake-sim --games 20 --wrap --seed x --max-ticks");
+        assert!(args.flag("--wrap"));
+        assert_eq!(args.parsed::<u64>("--games", "a number of games").unwrap(), Some(20));
+        assert_eq!(args.parsed::<u64>("--controller", "a name").unwrap(), None);
+        assert_eq!(args.parsed::<u64>("--seed", "a number").unwrap_err().to_string(), "--seed expects a number");
+        assert_eq!(args.value("--max-ticks").unwrap_err().to_string(), "--max-ticks expects a value");
+    }
+
+    #[test]
+    fn builds_the_game_config_from_options() {
+        let config = args("snake-sim --wrap --difficulty hard --food 3").game_config().unwrap();
+        assert_eq!(config.edge_policy, EdgePolicy::Wrap);
+        assert_eq!(config.speed, Difficulty::Hard.speed_curve());
+        assert_eq!(config.food_count, FoodCount::Fixed(3));
+
+        assert!(args("snake-sim --layout maze-of-nothing").game_config().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use std::{collections::HashMap, time::Instant};
+
+use anyhow::Context;
+use wgpu_snake_game::{
+    args::Args,
+    logic::{controller, Controller, GameOutcome, Input, SnakeGameLogic},
+};
+
+// runs many games without a window and prints how they went:
+//
+//     snake-sim --games 1000 --controller greedy --seed 0 --max-ticks 100000 [game options]
+//
+// game `i` uses seed `seed + i`, so a run can be repeated exactly. the game options are the same as
+// for the game itself (`--level`, `--wrap`, `--layout`, `--players <count>`, ...). with several snakes
+// `--controller` takes a comma separated list that is repeated over the snakes, and the length and
+// score are those of the first snake.
+
+#[derive(Default)]
+struct Stats {
+    games: u64,
+    ticks: u64,
+    length: u64,
+    best_length: usize,
+    points: u64,
+    outcomes: HashMap<String, u64>,
+}
+
+impl Stats {
+    fn add(&mut self, logic: &SnakeGameLogic) {
+        self.games += 1;
+        self.ticks += logic.tick();
+        let snake = &logic.snakes()[0];
+        self.length += snake.length() as u64;
+        self.best_length = self.best_length.max(snake.length());
+        self.points += snake.score().points();
+
+        let outcome = match logic.outcome() {
+            Some(GameOutcome::Died(cause)) => cause.to_string(),
+            Some(GameOutcome::Won) => "won".to_string(),
+            Some(GameOutcome::PlayerWon(snake)) => format!("player {} won", snake + 1),
+            Some(GameOutcome::Draw) => "draw".to_string(),
+            None => "ran out of ticks".to_string(),
+        };
+        *self.outcomes.entry(outcome).or_default() += 1;
+    }
+
+    fn mean(&self, total: u64) -> f64 {
+        total as f64 / self.games.max(1) as f64
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::from_env();
+    let config = args.game_config()?;
+    let games: u64 = args.parsed("--games", "a number of games")?.unwrap_or(1000);
+    let seed: u64 = args.parsed("--seed", "a number")?.unwrap_or(0);
+    let max_ticks: u64 = args.parsed("--max-ticks", "a number of ticks")?.unwrap_or(100_000);
+    let controller_name = args.value("--controller")?.unwrap_or("greedy");
+    let controller_names: Vec<&str> = controller_name.split(',').map(str::trim).collect();
+    for name in &controller_names {
+        controller::by_name(name, 0).with_context(|| {
+            format!("unknown controller: {}, expected straight, random, greedy, pathfinding or hamiltonian", name)
+        })?;
+    }
+    let snakes = config.spawns().len();
+
+    let mut stats = Stats::default();
+    let start = Instant::now();
+    for game in 0..games {
+        let game_seed = seed.wrapping_add(game);
+        let mut logic = SnakeGameLogic::new(config.clone(), game_seed);
+        // the controllers get their own seeds so they do not change where food spawns
+        let mut controllers: Vec<Box<dyn Controller>> = (0..snakes)
+            .filter_map(|snake| controller::by_name(controller_names[snake % controller_names.len()], (game_seed ^ 0x5eed).wrapping_add(snake as u64)))
+            .collect();
+
+        while !logic.is_over() && logic.tick() < max_ticks {
+            let inputs: Vec<Input> = controllers
+                .iter_mut()
+                .enumerate()
+                .filter(|(snake, _)| logic.snakes()[*snake].is_alive())
+                .filter_map(|(snake, controller)| controller.decide(&logic, snake).map(|direction| Input::Turn { snake, direction }))
+                .collect();
+            logic.step(&inputs);
+        }
+
+        stats.add(&logic);
+    }
+    let elapsed = start.elapsed();
+
+    println!("games: {} ({}, seeds {}..{})", stats.games, controller_name, seed, seed.wrapping_add(games));
+    println!("mean length: {:.2} (best {})", stats.mean(stats.length), stats.best_length);
+    println!("mean survival: {:.1} ticks", stats.mean(stats.ticks));
+    println!("mean score: {:.1}", stats.mean(stats.points));
+
+    let mut outcomes: Vec<_> = stats.outcomes.iter().collect();
+    outcomes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    println!("outcomes:");
+    for (outcome, count) in outcomes {
+        println!("  {}: {} ({:.1}%)", outcome, count, *count as f64 * 100.0 / stats.games.max(1) as f64);
+    }
+
+    println!(
+        "simulated {} ticks in {:.2?} ({:.0} ticks/s)",
+        stats.ticks,
+        elapsed,
+        stats.ticks as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu_snake_game::logic::GameConfig;
+
+    use super::*;
+
+    fn run(controller_name: &str, games: u64) -> Stats {
+        let mut stats = Stats::default();
+        for seed in 0..games {
+            let mut logic = SnakeGameLogic::new(GameConfig::default(), seed);
+            let mut controller = controller::by_name(controller_name, seed).unwrap();
+            while !logic.is_over() && logic.tick() < 5000 {
+                let inputs: Vec<Input> = controller.decide(&logic, 0).map(|direction| Input::Turn { snake: 0, direction }).into_iter().collect();
+                logic.step(&inputs);
+            }
+            stats.add(&logic);
+        }
+        stats
+    }
+
+    #[test]
+    fn greedy_outplays_going_straight() {
+        let straight = run("straight", 20);
+        let greedy = run("greedy", 20);
+
+        assert_eq!(straight.games, 20);
+        assert_eq!(straight.outcomes.values().sum::<u64>(), 20);
+        assert!(greedy.mean(greedy.ticks) > straight.mean(straight.ticks));
+        assert!(greedy.mean(greedy.points) > straight.mean(straight.points));
+        assert!(greedy.best_length > straight.best_length);
+    }
+}
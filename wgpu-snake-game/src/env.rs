@@ -0,0 +1,292 @@
+use crate::logic::{Direction, EdgePolicy, FoodKind, GameConfig, GameEvent, GameOutcome, Input, Position, SnakeGameLogic};
+
+mod observation;
+mod reward;
+mod batch;
+
+pub use batch::BatchEnv;
+pub use observation::ObservationKind;
+pub use reward::RewardConfig;
+
+// what an agent can do each tick, relative to where the snake is heading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Straight,
+    Left,
+    Right,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] = [Action::Straight, Action::Left, Action::Right];
+
+    pub fn from_index(index: usize) -> Option<Action> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(&self) -> usize {
+        match self {
+            Action::Straight => 0,
+            Action::Left => 1,
+            Action::Right => 2,
+        }
+    }
+
+    pub fn direction(&self, heading: Direction) -> Direction {
+        match self {
+            Action::Straight => heading,
+            Action::Left => heading.turn_left(),
+            Action::Right => heading.turn_right(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvConfig {
+    pub game: GameConfig,
+    pub reward: RewardConfig,
+    pub observation: ObservationKind,
+    // an episode is cut short after this many ticks, or this many ticks without eating, so a
+    // policy that circles forever still ends
+    pub max_ticks: Option<u64>,
+    pub max_ticks_without_food: Option<u64>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            reward: RewardConfig::default(),
+            observation: ObservationKind::default(),
+            max_ticks: None,
+            max_ticks_without_food: Some(1000),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepInfo {
+    pub tick: u64,
+    pub length: usize,
+    pub points: u64,
+    pub outcome: Option<GameOutcome>,
+    // the episode hit a tick limit rather than ending in the game
+    pub truncated: bool,
+    pub events: Vec<GameEvent>,
+}
+
+// the snake the agent steers; any others in the config keep going straight
+const AGENT: usize = 0;
+
+// a gym-style wrapper for training agents: one `step` is one game tick, with no window or clock
+#[derive(Debug, Clone)]
+pub struct SnakeEnv {
+    config: EnvConfig,
+    logic: SnakeGameLogic,
+    last_meal: u64,
+    truncated: bool,
+}
+
+impl SnakeEnv {
+    pub fn new(config: EnvConfig, seed: u64) -> Self {
+        let logic = SnakeGameLogic::new(config.game.clone(), seed);
+
+        Self { config, logic, last_meal: 0, truncated: false }
+    }
+
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    pub fn logic(&self) -> &SnakeGameLogic {
+        &self.logic
+    }
+
+    // the dimensions of every observation, outermost first
+    pub fn observation_shape(&self) -> Vec<usize> {
+        self.config.observation.shape(self.logic.board())
+    }
+
+    pub fn observation_len(&self) -> usize {
+        self.config.observation.len(self.logic.board())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.logic.is_over() || !self.logic.snakes()[AGENT].is_alive() || self.truncated
+    }
+
+    // starts a new episode and returns its first observation
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.restart(seed);
+        self.observe()
+    }
+
+    fn restart(&mut self, seed: u64) {
+        self.logic = SnakeGameLogic::new(self.config.game.clone(), seed);
+        self.last_meal = 0;
+        self.truncated = false;
+    }
+
+    pub fn observe(&self) -> Vec<f32> {
+        let mut observation = vec![0.0; self.observation_len()];
+        self.observe_into(&mut observation);
+        observation
+    }
+
+    // writes the observation into `observation`, which must be `observation_len` long
+    pub fn observe_into(&self, observation: &mut [f32]) {
+        self.config.observation.encode(&self.logic, observation);
+    }
+
+    pub fn step(&mut self, action: Action) -> (Vec<f32>, f32, bool, StepInfo) {
+        let mut observation = vec![0.0; self.observation_len()];
+        let (reward, done, info) = self.step_into(action, &mut observation);
+        (observation, reward, done, info)
+    }
+
+    // like `step`, but writes the observation into `observation` instead of allocating one
+    pub fn step_into(&mut self, action: Action, observation: &mut [f32]) -> (f32, bool, StepInfo) {
+        // a finished episode stays finished until the next reset
+        if self.is_done() {
+            self.observe_into(observation);
+            return (0.0, true, self.info(Vec::new()));
+        }
+
+        let distance = food_distance(&self.logic);
+        let direction = action.direction(self.logic.snakes()[AGENT].heading());
+        let events = self.logic.step(&[Input::Turn { snake: AGENT, direction }]);
+
+        if events.iter().any(|event| matches!(event, GameEvent::AteFood { snake: AGENT, .. })) {
+            self.last_meal = self.logic.tick();
+        }
+        let tick = self.logic.tick();
+        self.truncated = !self.is_done()
+            && (self.config.max_ticks.is_some_and(|max| tick >= max)
+                || self.config.max_ticks_without_food.is_some_and(|max| tick - self.last_meal >= max));
+
+        let reward = self.config.reward.reward(distance, &self.logic, &events);
+        self.observe_into(observation);
+        (reward, self.is_done(), self.info(events))
+    }
+
+    fn info(&self, events: Vec<GameEvent>) -> StepInfo {
+        StepInfo {
+            tick: self.logic.tick(),
+            length: self.logic.snakes()[AGENT].length(),
+            points: self.logic.snakes()[AGENT].score().points(),
+            outcome: self.logic.outcome(),
+            truncated: self.truncated,
+            events,
+        }
+    }
+}
+
+// the shortest offset from the head to the nearest food worth eating, across the edges if they wrap
+fn nearest_food(logic: &SnakeGameLogic) -> Option<Position> {
+    let head = *logic.snakes()[AGENT].head();
+    let board = logic.board();
+    let shortest = |delta: i32, size: i32| match logic.edge_policy() {
+        EdgePolicy::Walls => delta,
+        EdgePolicy::Wrap => {
+            let delta = delta.rem_euclid(size);
+            if delta > size / 2 { delta - size } else { delta }
+        }
+    };
+
+    logic
+        .food
+        .items()
+        .iter()
+        .filter(|item| item.kind != FoodKind::Poison)
+        .map(|item| {
+            let delta = item.position - head;
+            Position::new(shortest(delta.x(), board.width()), shortest(delta.y(), board.height()))
+        })
+        .min_by_key(|offset| offset.manhattan_distance(&Position::ZERO))
+}
+
+fn food_distance(logic: &SnakeGameLogic) -> Option<u32> {
+    nearest_food(logic).map(|offset| offset.manhattan_distance(&Position::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{Board, FoodCount};
+
+    fn config(game: GameConfig) -> EnvConfig {
+        EnvConfig { game, ..EnvConfig::default() }
+    }
+
+    #[test]
+    fn observations_have_the_advertised_length() {
+        for observation in [ObservationKind::Grid, ObservationKind::Egocentric { radius: 3 }, ObservationKind::Features] {
+            let mut env = SnakeEnv::new(EnvConfig { observation, ..EnvConfig::default() }, 0);
+            assert_eq!(env.observation_len(), env.observation_shape().iter().product::<usize>());
+            assert_eq!(env.reset(3).len(), env.observation_len());
+            assert_eq!(env.step(Action::Left).0.len(), env.observation_len());
+        }
+    }
+
+    #[test]
+    fn the_same_seed_and_actions_give_the_same_episode() {
+        let play = || {
+            let mut env = SnakeEnv::new(EnvConfig::default(), 0);
+            let mut steps = vec![(env.reset(5), 0.0, false)];
+            for tick in 0..200 {
+                let (observation, reward, done, _) = env.step(Action::ALL[tick / 7 % 3]);
+                steps.push((observation, reward, done));
+            }
+            steps
+        };
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn eating_pays_and_dying_costs() {
+        let game = GameConfig { board: Board::new(8, 4), initial_food: vec![Position::new(2, 0)], ..GameConfig::default() };
+        let mut env = SnakeEnv::new(config(game), 0);
+
+        assert_eq!(env.step(Action::Straight).1, 0.0);
+        let (_, reward, done, info) = env.step(Action::Straight);
+        assert_eq!((reward, done, info.points > 0), (1.0, false, true));
+
+        let (mut reward, mut done) = (0.0, false);
+        while !done {
+            (_, reward, done, _) = env.step(Action::Straight);
+        }
+        assert_eq!(reward, -1.0);
+        assert!(!env.step(Action::Straight).3.truncated);
+    }
+
+    #[test]
+    fn episodes_without_food_are_cut_short() {
+        let game = GameConfig { edge_policy: EdgePolicy::Wrap, food_count: FoodCount::Fixed(0), ..GameConfig::default() };
+        let mut env = SnakeEnv::new(EnvConfig { max_ticks_without_food: Some(5), ..config(game) }, 0);
+
+        for _ in 0..4 {
+            assert!(!env.step(Action::Straight).2);
+        }
+        let (_, reward, done, info) = env.step(Action::Straight);
+        assert_eq!((reward, done, info.truncated, info.outcome), (0.0, true, true, None));
+    }
+
+    #[test]
+    fn a_finished_episode_stays_finished() {
+        let game = GameConfig { board: Board::new(4, 4), ..GameConfig::default() };
+        let mut env = SnakeEnv::new(config(game), 0);
+        while !env.step(Action::Straight).2 {}
+
+        let tick = env.logic().tick();
+        let (_, reward, done, info) = env.step(Action::Left);
+        assert_eq!((reward, done, info.tick, info.events), (0.0, true, tick, Vec::new()));
+    }
+
+    #[test]
+    fn filling_the_board_pays_the_win() {
+        let game = GameConfig { board: Board::new(1, 1), edge_policy: EdgePolicy::Wrap, ..GameConfig::default() };
+        let mut env = SnakeEnv::new(config(game), 0);
+
+        let (_, reward, done, info) = env.step(Action::Straight);
+        assert_eq!((reward, done, info.outcome), (1.0, true, Some(GameOutcome::Won)));
+    }
+}
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use super::{Action, EnvConfig, SnakeEnv, StepInfo};
+
+// one game of the batch and how many episodes it has started
+#[derive(Debug, Clone)]
+struct Slot {
+    env: SnakeEnv,
+    seed: u64,
+    episodes: u64,
+}
+
+impl Slot {
+    // episode `k` of game `i` uses seed `seed + i + k * count`, so every episode of a batch gets
+    // its own seed and a run can be repeated no matter how many threads step it
+    fn restart(&mut self, count: u64) {
+        self.env.restart(self.seed.wrapping_add(self.episodes.wrapping_mul(count)));
+        self.episodes += 1;
+    }
+}
+
+// steps many independent games in lockstep for training. the observations of all games sit in one
+// buffer, game after game, and a finished game starts its next episode right away: its entry in
+// `observations` is then the new episode's first observation, while `dones` and `infos` still
+// describe the one that ended
+#[derive(Debug, Clone)]
+pub struct BatchEnv {
+    slots: Vec<Slot>,
+    threads: usize,
+    // kept for the life of the batch, since starting threads every tick costs more than the games
+    pool: Option<Arc<ThreadPool>>,
+    observation_len: usize,
+    observations: Vec<f32>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    infos: Vec<StepInfo>,
+}
+
+impl BatchEnv {
+    pub fn new(config: EnvConfig, count: usize, seed: u64) -> Self {
+        let env = SnakeEnv::new(config, seed);
+        let observation_len = env.observation_len();
+        let slots = (0..count)
+            .map(|_| Slot { env: env.clone(), seed: 0, episodes: 0 })
+            .collect();
+
+        let mut batch = Self {
+            slots,
+            threads: 1,
+            pool: None,
+            observation_len,
+            observations: vec![0.0; count * observation_len],
+            rewards: vec![0.0; count],
+            dones: vec![false; count],
+            infos: vec![env.info(Vec::new()); count],
+        };
+        batch.reset(seed);
+        batch
+    }
+
+    // steps the games on up to `threads` threads; one, the default, keeps everything on the caller's
+    pub fn with_threads(mut self, threads: usize) -> anyhow::Result<Self> {
+        self.threads = threads.max(1);
+        self.pool = None;
+        if self.threads > 1 {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(self.threads)
+                .thread_name(|index| format!("batch-env-{}", index))
+                .build()
+                .context("failed to start the batch threads")?;
+            self.pool = Some(Arc::new(pool));
+        }
+
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn env(&self, index: usize) -> Option<&SnakeEnv> {
+        self.slots.get(index).map(|slot| &slot.env)
+    }
+
+    // the shape of one game's observation; the buffer holds `len` of them
+    pub fn observation_shape(&self) -> Vec<usize> {
+        self.slots.first().map_or_else(Vec::new, |slot| slot.env.observation_shape())
+    }
+
+    pub fn observation_len(&self) -> usize {
+        self.observation_len
+    }
+
+    // starts every game over, game `i` with seed `seed + i`, and returns the first observations
+    pub fn reset(&mut self, seed: u64) -> &[f32] {
+        let count = self.slots.len() as u64;
+        let chunks = self.slots.iter_mut().zip(self.observations.chunks_mut(self.observation_len));
+        for (index, (slot, observation)) in chunks.enumerate() {
+            slot.seed = seed.wrapping_add(index as u64);
+            slot.episodes = 0;
+            slot.restart(count);
+            slot.env.observe_into(observation);
+        }
+        self.rewards.fill(0.0);
+        self.dones.fill(false);
+
+        &self.observations
+    }
+
+    // runs one tick of every game, `actions[i]` steering game `i`, and returns the observations,
+    // rewards, dones and infos
+    pub fn step(&mut self, actions: &[Action]) -> (&[f32], &[f32], &[bool], &[StepInfo]) {
+        assert_eq!(actions.len(), self.slots.len(), "expected one action per game");
+
+        let count = self.slots.len() as u64;
+        let threads = self.threads.min(self.slots.len()).max(1);
+        let games_per_thread = self.slots.len().div_ceil(threads).max(1);
+        let chunks = self
+            .slots
+            .chunks_mut(games_per_thread)
+            .zip(actions.chunks(games_per_thread))
+            .zip(self.observations.chunks_mut(games_per_thread * self.observation_len))
+            .zip(self.rewards.chunks_mut(games_per_thread))
+            .zip(self.dones.chunks_mut(games_per_thread))
+            .zip(self.infos.chunks_mut(games_per_thread))
+            .map(|(((((slots, actions), observations), rewards), dones), infos)| Chunk {
+                slots,
+                actions,
+                observations,
+                rewards,
+                dones,
+                infos,
+            });
+
+        match &self.pool {
+            Some(pool) if threads > 1 => pool.scope(|scope| {
+                for chunk in chunks {
+                    scope.spawn(move |_| chunk.step(count));
+                }
+            }),
+            _ => chunks.for_each(|chunk| chunk.step(count)),
+        }
+
+        (&self.observations, &self.rewards, &self.dones, &self.infos)
+    }
+
+    pub fn observations(&self) -> &[f32] {
+        &self.observations
+    }
+
+    pub fn rewards(&self) -> &[f32] {
+        &self.rewards
+    }
+
+    pub fn dones(&self) -> &[bool] {
+        &self.dones
+    }
+
+    pub fn infos(&self) -> &[StepInfo] {
+        &self.infos
+    }
+}
+
+// the games one thread steps, with their parts of the output buffers
+struct Chunk<'a> {
+    slots: &'a mut [Slot],
+    actions: &'a [Action],
+    observations: &'a mut [f32],
+    rewards: &'a mut [f32],
+    dones: &'a mut [bool],
+    infos: &'a mut [StepInfo],
+}
+
+impl Chunk<'_> {
+    fn step(self, count: u64) {
+        let observation_len = self.observations.len() / self.slots.len();
+        let observations = self.observations.chunks_mut(observation_len);
+        let games = self.slots.iter_mut().zip(self.actions).zip(observations);
+
+        for (index, ((slot, action), observation)) in games.enumerate() {
+            let (reward, done, info) = slot.env.step_into(*action, observation);
+            if done {
+                slot.restart(count);
+                slot.env.observe_into(observation);
+            }
+
+            self.rewards[index] = reward;
+            self.dones[index] = done;
+            self.infos[index] = info;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(threads: usize) -> (Vec<f32>, Vec<f32>, Vec<bool>) {
+        let mut batch = BatchEnv::new(EnvConfig::default(), 7, 11).with_threads(threads).unwrap();
+        let (mut rewards, mut dones) = (Vec::new(), Vec::new());
+        for tick in 0..300 {
+            let actions: Vec<Action> = (0..batch.len()).map(|game| Action::ALL[(game + tick / 5) % 3]).collect();
+            let (_, step_rewards, step_dones, _) = batch.step(&actions);
+            rewards.extend_from_slice(step_rewards);
+            dones.extend_from_slice(step_dones);
+        }
+
+        (batch.observations().to_vec(), rewards, dones)
+    }
+
+    #[test]
+    fn threads_do_not_change_the_results() {
+        let single = run(1);
+        assert!(single.2.iter().any(|done| *done));
+        assert_eq!(run(2), single);
+        assert_eq!(run(3), single);
+    }
+
+    #[test]
+    fn finished_games_start_their_next_episode() {
+        let config = EnvConfig::default();
+        let mut batch = BatchEnv::new(config.clone(), 2, 5);
+        let mut env = SnakeEnv::new(config.clone(), 5);
+        let length = batch.observation_len();
+
+        // game 0 runs straight into the wall, then starts over with its second seed, 5 + 0 + 1 * 2
+        loop {
+            let (observation, reward, done, info) = env.step(Action::Straight);
+            batch.step(&[Action::Straight, Action::Straight]);
+            assert_eq!((batch.rewards()[0], batch.dones()[0], &batch.infos()[0]), (reward, done, &info));
+            if done {
+                let first = SnakeEnv::new(config, 0).reset(7);
+                assert_eq!(&batch.observations()[..length], first.as_slice());
+                assert_eq!(batch.env(0).unwrap().logic().tick(), 0);
+                break;
+            }
+            assert_eq!(&batch.observations()[..length], observation.as_slice());
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::logic::{Board, Cell, Direction, EdgePolicy, FoodKind, Position, SnakeGameLogic};
+
+use super::{nearest_food, AGENT};
+
+const GRID_CHANNELS: usize = 5;
+const EGOCENTRIC_CHANNELS: usize = 4;
+const FEATURES: usize = 12;
+
+// how the game is encoded for the agent. every encoding is a flat list of floats, laid out
+// channel by channel and row by row, with rows counting from the top like an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObservationKind {
+    // the whole board, one channel each for the head, the body, obstacles and other snakes, food and poison
+    #[default]
+    Grid,
+    // the `2 * radius + 1` cells square around the head, turned so the snake always faces up. one
+    // channel each for blocked cells (obstacles and walls), the snake, food and poison
+    Egocentric { radius: i32 },
+    // danger straight ahead, to the left and to the right; the heading as up, down, left and right;
+    // whether the nearest food is up, down, left or right of the head; and the length as a fraction
+    // of the board
+    Features,
+}
+
+impl ObservationKind {
+    pub fn shape(&self, board: &Board) -> Vec<usize> {
+        match self {
+            ObservationKind::Grid => vec![GRID_CHANNELS, board.height() as usize, board.width() as usize],
+            ObservationKind::Egocentric { radius } => {
+                let size = (2 * (*radius).max(0) + 1) as usize;
+                vec![EGOCENTRIC_CHANNELS, size, size]
+            }
+            ObservationKind::Features => vec![FEATURES],
+        }
+    }
+
+    pub fn len(&self, board: &Board) -> usize {
+        self.shape(board).iter().product()
+    }
+
+    // fills `out`, which must be `len` long
+    pub fn encode(&self, logic: &SnakeGameLogic, out: &mut [f32]) {
+        assert_eq!(out.len(), self.len(logic.board()), "wrong observation buffer length");
+        out.fill(0.0);
+
+        match self {
+            ObservationKind::Grid => encode_grid(logic, out),
+            ObservationKind::Egocentric { radius } => encode_egocentric(logic, (*radius).max(0), out),
+            ObservationKind::Features => encode_features(logic, out),
+        }
+    }
+}
+
+fn encode_grid(logic: &SnakeGameLogic, out: &mut [f32]) {
+    let board = logic.board();
+    let (width, height) = (board.width(), board.height());
+    let plane = board.cell_count();
+    // from the top row down, unlike `Board::index_of`
+    let index = |position: &Position| {
+        board.contains(position).then(|| {
+            let offset = *position - board.origin();
+            ((height - 1 - offset.y()) * width + offset.x()) as usize
+        })
+    };
+
+    let snake = &logic.snakes()[AGENT];
+    if let Some(head) = index(snake.head()) {
+        out[head] = 1.0;
+    }
+    for segment in snake.body() {
+        if let Some(segment) = index(segment) {
+            out[plane + segment] = 1.0;
+        }
+    }
+
+    let others = logic.snakes().iter().enumerate().filter(|(index, other)| *index != AGENT && other.is_alive());
+    let other_cells = others.flat_map(|(_, other)| std::iter::once(other.head()).chain(other.body()));
+    for obstacle in logic.obstacles().iter().chain(other_cells) {
+        if let Some(cell) = index(obstacle) {
+            out[2 * plane + cell] = 1.0;
+        }
+    }
+    for item in logic.food.items() {
+        let channel = if item.kind == FoodKind::Poison { 4 } else { 3 };
+        if let Some(cell) = index(&item.position) {
+            out[channel * plane + cell] = 1.0;
+        }
+    }
+}
+
+fn encode_egocentric(logic: &SnakeGameLogic, radius: i32, out: &mut [f32]) {
+    let snake = &logic.snakes()[AGENT];
+    let heading = snake.heading();
+    let forward = heading.convert_to_position();
+    let right = heading.turn_right().convert_to_position();
+    let size = (2 * radius + 1) as usize;
+    let plane = size * size;
+
+    for row in 0..size {
+        for column in 0..size {
+            let ahead = radius - row as i32;
+            let side = column as i32 - radius;
+            let mut position = *snake.head() + forward * ahead + right * side;
+            if logic.edge_policy() == EdgePolicy::Wrap {
+                position = logic.board().wrap(position);
+            }
+
+            let channel = match logic.grid().get(&position) {
+                None | Some(Cell::Obstacle) => 0,
+                Some(Cell::Snake) => 1,
+                Some(Cell::Food(FoodKind::Poison)) => 3,
+                Some(Cell::Food(_)) => 2,
+                Some(Cell::Empty) => continue,
+            };
+            out[channel * plane + row * size + column] = 1.0;
+        }
+    }
+}
+
+fn encode_features(logic: &SnakeGameLogic, out: &mut [f32]) {
+    let snake = &logic.snakes()[AGENT];
+    let heading = snake.heading();
+    let is_danger = |direction| match logic.grid().get(&logic.neighbour(snake.head(), direction)) {
+        Some(Cell::Empty) => false,
+        Some(Cell::Food(kind)) => kind == FoodKind::Poison,
+        _ => true,
+    };
+
+    let food = nearest_food(logic).unwrap_or(Position::ZERO);
+    let features = [
+        is_danger(heading),
+        is_danger(heading.turn_left()),
+        is_danger(heading.turn_right()),
+        heading == Direction::Up,
+        heading == Direction::Down,
+        heading == Direction::Left,
+        heading == Direction::Right,
+        food.y() > 0,
+        food.y() < 0,
+        food.x() < 0,
+        food.x() > 0,
+    ];
+
+    for (value, feature) in out.iter_mut().zip(features) {
+        *value = if feature { 1.0 } else { 0.0 };
+    }
+    out[FEATURES - 1] = snake.length() as f32 / logic.board().cell_count() as f32;
+}
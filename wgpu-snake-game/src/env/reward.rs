@@ -0,0 +1,58 @@
+use crate::logic::{FoodKind, GameEvent, GameOutcome, SnakeGameLogic};
+
+use super::{food_distance, AGENT};
+
+// how much each thing that can happen in a tick is worth to the agent; the terms are summed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RewardConfig {
+    // for every food eaten, poison aside
+    pub food: f32,
+    // for every tick the snake survives
+    pub survival: f32,
+    // once, when the snake dies; usually negative
+    pub death: f32,
+    // once, when the snake fills the board or wins a game with several snakes
+    pub win: f32,
+    // shaping: for every cell the head got closer to the nearest food, negative when it moved away
+    pub distance: f32,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            food: 1.0,
+            survival: 0.0,
+            death: -1.0,
+            win: 1.0,
+            distance: 0.0,
+        }
+    }
+}
+
+impl RewardConfig {
+    // the reward for the tick that produced `events`; `distance` is the food distance before it
+    pub(super) fn reward(&self, distance: Option<u32>, logic: &SnakeGameLogic, events: &[GameEvent]) -> f32 {
+        let mut reward = self.survival;
+        let mut ate = false;
+        for event in events {
+            match event {
+                GameEvent::AteFood { snake: AGENT, kind, .. } if *kind != FoodKind::Poison => {
+                    reward += self.food;
+                    ate = true;
+                }
+                GameEvent::Died { snake: AGENT, .. } => reward += self.death - self.survival,
+                GameEvent::GameOver { outcome: GameOutcome::Won | GameOutcome::PlayerWon(AGENT) } => reward += self.win,
+                _ => {}
+            }
+        }
+
+        // eating moves the target and dying ends the episode, so the distance says nothing then
+        if !ate && !logic.is_over() && logic.snakes()[AGENT].is_alive() {
+            if let (Some(before), Some(after)) = (distance, food_distance(logic)) {
+                reward += self.distance * (before as f32 - after as f32);
+            }
+        }
+
+        reward
+    }
+}
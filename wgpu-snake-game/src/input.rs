@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+
+use winit::{event::{ElementState, WindowEvent}, keyboard::{KeyCode, PhysicalKey}};
+
+use crate::logic::{Controller, Direction, SnakeGameLogic};
+
+// how many people can share the keyboard, one per set of direction keys
+pub const KEYBOARD_PLAYERS: usize = 2;
+
+// at most this many turns are remembered between ticks, like the snake's own queue
+const MAX_BUFFERED_TURNS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    // `player` is which set of keys was used: 0 for the arrows, 1 for WASD
+    Turn { player: usize, direction: Direction },
+    Confirm,
+    TogglePause,
+    Restart,
+    Save,
+    Load,
+    ToggleAutopilot,
+}
+
+// the player at the keyboard: turns pressed between ticks are handed over one per tick
+#[derive(Debug, Clone, Default)]
+pub struct KeyboardController {
+    turns: VecDeque<Direction>,
+}
+
+impl KeyboardController {
+    pub fn press(&mut self, direction: Direction) {
+        if self.turns.len() < MAX_BUFFERED_TURNS {
+            self.turns.push_back(direction);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+}
+
+impl Controller for KeyboardController {
+    fn decide(&mut self, logic: &SnakeGameLogic, snake: usize) -> Option<Direction> {
+        // skip turns the snake would ignore so they do not hold up the next one for a tick
+        let heading = logic.snakes()[snake].heading();
+        while let Some(direction) = self.turns.pop_front() {
+            if direction != heading && direction != heading.opposite() {
+                return Some(direction);
+            }
+        }
+
+        None
+    }
+}
+
+pub fn window_event(event: &WindowEvent) -> Option<Action> {
+    let WindowEvent::KeyboardInput { event, is_synthetic: false, .. } = event else {
+        return None;
+    };
+
+    if event.state != ElementState::Pressed || event.repeat {
+        return None;
+    }
+
+    match event.physical_key {
+        PhysicalKey::Code(KeyCode::ArrowUp) => Some(Action::Turn { player: 0, direction: Direction::Up }),
+        PhysicalKey::Code(KeyCode::ArrowDown) => Some(Action::Turn { player: 0, direction: Direction::Down }),
+        PhysicalKey::Code(KeyCode::ArrowLeft) => Some(Action::Turn { player: 0, direction: Direction::Left }),
+        PhysicalKey::Code(KeyCode::ArrowRight) => Some(Action::Turn { player: 0, direction: Direction::Right }),
+        PhysicalKey::Code(KeyCode::KeyW) => Some(Action::Turn { player: 1, direction: Direction::Up }),
+        PhysicalKey::Code(KeyCode::KeyS) => Some(Action::Turn { player: 1, direction: Direction::Down }),
+        PhysicalKey::Code(KeyCode::KeyA) => Some(Action::Turn { player: 1, direction: Direction::Left }),
+        PhysicalKey::Code(KeyCode::KeyD) => Some(Action::Turn { player: 1, direction: Direction::Right }),
+        PhysicalKey::Code(KeyCode::Enter | KeyCode::Space) => Some(Action::Confirm),
+        PhysicalKey::Code(KeyCode::KeyP | KeyCode::Escape) => Some(Action::TogglePause),
+        PhysicalKey::Code(KeyCode::KeyR) => Some(Action::Restart),
+        PhysicalKey::Code(KeyCode::F5) => Some(Action::Save),
+        PhysicalKey::Code(KeyCode::F9) => Some(Action::Load),
+        PhysicalKey::Code(KeyCode::Tab) => Some(Action::ToggleAutopilot),
+        _ => None,
+    }
+}
@@ -1,2 +1,5 @@
 pub mod renderer;
-pub mod logic;
\ No newline at end of file
+pub mod logic;
+pub mod input;
+pub mod args;
+pub mod env;
\ No newline at end of file
@@ -1,104 +1,642 @@
-use anyhow::Result;
+use std::time::Duration;
 
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 use timer::LogicTimer;
-use winit::{event::{DeviceId, KeyEvent, WindowEvent}, keyboard::{KeyCode, PhysicalKey}};
 
+pub mod math;
+mod board;
 mod player_snake;
 mod food;
 mod timer;
+mod outcome;
+mod phase;
+mod config;
+mod layout;
+mod level;
+mod speed;
+mod score;
+mod event;
+mod occupancy;
+mod save;
+mod replay;
+mod autopilot;
+pub mod controller;
 
-mod math {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct Position {
-        x: i32,
-        y: i32,
-    }
+pub use autopilot::{Autopilot, AutopilotMode};
+pub use board::Board;
+pub use controller::Controller;
+pub use config::{EdgePolicy, FoodCount, GameConfig};
+pub use event::GameEvent;
+pub use food::{FoodEffect, FoodItem, FoodKind};
+pub use layout::Layout;
+pub use level::{Level, ParseError};
+pub use occupancy::{Cell, OccupancyGrid};
+pub use score::Score;
+pub use speed::{Difficulty, SpeedCurve};
+pub use outcome::{DeathCause, GameOutcome};
+pub use player_snake::PlayerSnake;
+pub use phase::GamePhase;
+pub use save::SAVE_VERSION;
+pub use replay::{Replay, ReplayPlayer, REPLAY_VERSION};
+pub use math::{Direction, Position, Rect};
 
-    impl Position {
-        pub fn new(x: i32, y: i32) -> Self {
-            Self { x, y }
-        }
+pub type GameRng = rand_chacha::ChaCha8Rng;
 
-        pub fn x(&self) -> i32 {
-            self.x
-        }
-
-        pub fn y(&self) -> i32 {
-            self.y
-        }
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Input {
+    Turn { snake: usize, direction: Direction },
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SnakeGameLogic {
-    pub player_snake: player_snake::PlayerSnake,
+    snakes: Vec<PlayerSnake>,
     pub food: food::Food,
-    width: i32,
-    height: i32,
+    config: GameConfig,
+    grid: OccupancyGrid,
     timer: LogicTimer,
+    rng: GameRng,
+    outcome: Option<GameOutcome>,
+    tick: u64,
+    // tick rate factor from a speed food and the tick it wears off at
+    speed_effect: Option<(f32, u64)>,
 }
 
 impl SnakeGameLogic {
-    pub fn new(width: i32, height: i32) -> Self {
-        let player_snake = player_snake::PlayerSnake::new();
-        let food = food::Food::new(width, height);
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        Self::with_rng(config, GameRng::seed_from_u64(seed))
+    }
+
+    pub fn with_rng(config: GameConfig, mut rng: GameRng) -> Self {
+        let snakes: Vec<PlayerSnake> = config
+            .spawns()
+            .iter()
+            .map(|&(position, direction)| PlayerSnake::new(position, direction))
+            .collect();
+
+        let mut grid = OccupancyGrid::new(config.board);
+        for obstacle in &config.obstacles {
+            grid.set(obstacle, Cell::Obstacle);
+        }
+        for snake in &snakes {
+            grid.set(snake.head(), Cell::Snake);
+        }
+
+        let food = food::Food::new(
+            config.food_count.count(&config.board),
+            &config.initial_food,
+            &mut grid,
+            &mut rng,
+        );
         let timer = LogicTimer::new();
 
-        Self { player_snake, food, width, height, timer }
+        Self {
+            snakes,
+            food,
+            config,
+            grid,
+            timer,
+            rng,
+            outcome: None,
+            tick: 0,
+            speed_effect: None,
+        }
     }
 
-    pub fn width(&self) -> i32 {
-        self.width
+    pub fn config(&self) -> &GameConfig {
+        &self.config
     }
 
-    pub fn height(&self) -> i32 {
-        self.height
+    pub fn board(&self) -> &Board {
+        &self.config.board
     }
 
-    fn update(&mut self) {
-        self.player_snake.update();
-        if self.food.try_eat_food(self.player_snake.head(), self.width, self.height) {
-            self.player_snake.grow();
-        }
+    pub fn edge_policy(&self) -> EdgePolicy {
+        self.config.edge_policy
+    }
+
+    pub fn snakes(&self) -> &[PlayerSnake] {
+        &self.snakes
+    }
+
+    // the speed follows the longest snake still playing
+    fn longest_length(&self) -> usize {
+        self.snakes.iter().filter(|snake| snake.is_alive()).map(PlayerSnake::length).max().unwrap_or(1)
+    }
+
+    pub fn ticks_per_second(&self) -> f32 {
+        let factor = self.speed_effect.map_or(1.0, |(factor, _)| factor);
+        self.config.speed.ticks_per_second(self.longest_length()) * factor
     }
 
-    fn keyboard_event(&mut self, _device_id: &DeviceId, event: &KeyEvent, is_synthetic: bool) {
-        if is_synthetic {
-            return;
+    pub fn speed_level(&self) -> u32 {
+        self.config.speed.level(self.longest_length())
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn obstacles(&self) -> &[Position] {
+        &self.config.obstacles
+    }
+
+    pub fn grid(&self) -> &OccupancyGrid {
+        &self.grid
+    }
+
+    // the cell a snake at `position` moves into, following the edge policy
+    pub fn neighbour(&self, position: &Position, direction: Direction) -> Position {
+        match self.edge_policy() {
+            EdgePolicy::Walls => position.step(direction),
+            EdgePolicy::Wrap => self.config.board.wrap(position.step(direction)),
         }
+    }
 
-        if event.state == winit::event::ElementState::Pressed {
-            match event.physical_key {
-                PhysicalKey::Code(KeyCode::ArrowUp) => {
-                    self.player_snake.set_direction(player_snake::Direction::Down);
-                }
-                PhysicalKey::Code(KeyCode::ArrowDown) => {
-                    self.player_snake.set_direction(player_snake::Direction::Up);
-                }
-                PhysicalKey::Code(KeyCode::ArrowLeft) => {
-                    self.player_snake.set_direction(player_snake::Direction::Left);
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.outcome
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    // the food already on the board, as the events that would have put it there. `step` only reports
+    // changes, so a subscriber that follows events starts from these after a new game or a load
+    pub fn spawn_events(&self) -> Vec<GameEvent> {
+        self.food
+            .items()
+            .iter()
+            .map(|item| GameEvent::FoodSpawned { kind: item.kind, position: item.position })
+            .collect()
+    }
+
+    fn apply_inputs(&mut self, inputs: &[Input]) {
+        for input in inputs {
+            match *input {
+                Input::Turn { snake, direction } => {
+                    if let Some(snake) = self.snakes.get_mut(snake).filter(|snake| snake.is_alive()) {
+                        snake.queue_direction(direction);
+                    }
                 }
-                PhysicalKey::Code(KeyCode::ArrowRight) => {
-                    self.player_snake.set_direction(player_snake::Direction::Right);
+            }
+        }
+    }
+
+    // must run after every tail has left its cell but before any head is written to the grid.
+    // `previous_heads` are where the heads were before this tick
+    fn check_death(&self, index: usize, previous_heads: &[Position]) -> Option<DeathCause> {
+        let snake = &self.snakes[index];
+        // two heads moving into the same cell, or through each other, take each other out
+        let head_on_head = self.snakes.iter().enumerate().any(|(other, other_snake)| {
+            other != index
+                && other_snake.is_alive()
+                && (other_snake.head() == snake.head()
+                    || (*other_snake.head() == previous_heads[index] && *snake.head() == previous_heads[other]))
+        });
+
+        match self.grid.get(snake.head()) {
+            None => Some(DeathCause::Wall),
+            Some(Cell::Obstacle) => Some(DeathCause::Obstacle),
+            _ if head_on_head => Some(DeathCause::OtherSnake),
+            Some(Cell::Snake) if snake.body().contains(snake.head()) => Some(DeathCause::SelfCollision),
+            Some(Cell::Snake) => Some(DeathCause::OtherSnake),
+            Some(Cell::Empty | Cell::Food(_)) => None,
+        }
+    }
+
+    // takes a snake out of the game and frees its body for the others; its head is only in the grid
+    // once it has been written there this tick
+    fn kill(&mut self, index: usize, cause: DeathCause, events: &mut Vec<GameEvent>) {
+        let snake = &mut self.snakes[index];
+        snake.kill(cause);
+        for segment in snake.body() {
+            self.grid.set(segment, Cell::Empty);
+        }
+        events.push(GameEvent::Died { snake: index, cause });
+    }
+
+    // the outcome once too few snakes are left to go on: a lone snake plays until it dies, several
+    // until at most one is left
+    fn outcome_of_deaths(&self) -> Option<GameOutcome> {
+        let alive: Vec<usize> = (0..self.snakes.len()).filter(|index| self.snakes[*index].is_alive()).collect();
+
+        match (self.snakes.as_slice(), alive.as_slice()) {
+            ([snake], []) => snake.death().map(GameOutcome::Died),
+            ([_], _) | (_, [_, _, ..]) => None,
+            (_, [survivor]) => Some(GameOutcome::PlayerWon(*survivor)),
+            (_, []) => Some(GameOutcome::Draw),
+        }
+    }
+
+    fn finish(&mut self, outcome: GameOutcome, events: &mut Vec<GameEvent>) {
+        self.outcome = Some(outcome);
+        events.push(GameEvent::GameOver { outcome });
+    }
+
+    // a full board with several snakes on it goes to the one still playing with the most points
+    fn outcome_by_score(&self) -> GameOutcome {
+        let alive = || self.snakes.iter().enumerate().filter(|(_, snake)| snake.is_alive());
+        let best = alive().map(|(_, snake)| snake.score().points()).max();
+        let mut leaders = alive().filter(|(_, snake)| Some(snake.score().points()) == best);
+
+        match (leaders.next(), leaders.next()) {
+            (Some((index, _)), None) => GameOutcome::PlayerWon(index),
+            _ => GameOutcome::Draw,
+        }
+    }
+
+    // advances the simulation by exactly one tick and returns everything that happened during it
+    pub fn step(&mut self, inputs: &[Input]) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.is_over() {
+            return events;
+        }
+
+        self.apply_inputs(inputs);
+        let ticks_per_second = self.ticks_per_second();
+        self.tick += 1;
+        for snake in &mut self.snakes {
+            snake.score_mut().update(self.tick);
+        }
+        if self.speed_effect.is_some_and(|(_, until)| self.tick >= until) {
+            self.speed_effect = None;
+        }
+
+        // every snake moves before any of them is checked, so a head may take a cell a tail left
+        // this very tick, whichever snake the tail belongs to
+        let board = self.config.board;
+        let edge_policy = self.edge_policy();
+        let previous_heads: Vec<Position> = self.snakes.iter().map(|snake| *snake.head()).collect();
+        for (index, snake) in self.snakes.iter_mut().enumerate().filter(|(_, snake)| snake.is_alive()) {
+            let length = snake.length();
+            let vacated = match edge_policy {
+                EdgePolicy::Walls => snake.update(|position| position),
+                EdgePolicy::Wrap => snake.update(|position| board.wrap(position)),
+            };
+            if let Some(tail) = vacated {
+                self.grid.set(&tail, Cell::Empty);
+            }
+            if snake.length() > length {
+                events.push(GameEvent::Grew { snake: index, length: snake.length() });
+            }
+        }
+
+        let deaths: Vec<(usize, DeathCause)> = (0..self.snakes.len())
+            .filter(|index| self.snakes[*index].is_alive())
+            .filter_map(|index| self.check_death(index, &previous_heads).map(|cause| (index, cause)))
+            .collect();
+        for (index, cause) in deaths {
+            self.kill(index, cause, &mut events);
+        }
+
+        for index in 0..self.snakes.len() {
+            if !self.snakes[index].is_alive() {
+                continue;
+            }
+
+            let head = *self.snakes[index].head();
+            let previous = self.grid.set(&head, Cell::Snake);
+
+            let eaten = match previous {
+                Some(Cell::Food(_)) => self.food.eat(&head),
+                _ => None,
+            };
+            if let Some(item) = eaten {
+                let speed_level = self.speed_level();
+                let points = self.snakes[index].score_mut().award(item.kind.points(), self.tick, speed_level);
+                events.push(GameEvent::AteFood { snake: index, kind: item.kind, position: item.position, points });
+
+                self.apply_food_effect(index, item.kind.effect(), &mut events);
+            }
+        }
+
+        if let Some(outcome) = self.outcome_of_deaths() {
+            self.finish(outcome, &mut events);
+            return events;
+        }
+
+        for item in self.food.expire(&mut self.grid, self.tick) {
+            events.push(GameEvent::FoodExpired { kind: item.kind, position: item.position });
+        }
+
+        let count = self.config.food_count.count(&self.config.board);
+        for item in self.food.refill(count, &mut self.grid, self.tick, &mut self.rng) {
+            events.push(GameEvent::FoodSpawned { kind: item.kind, position: item.position });
+        }
+
+        // nothing left to eat and nowhere to put more: the snakes have filled the board
+        if self.food.is_empty() && self.grid.free_count() == 0 {
+            let outcome = if self.snakes.len() == 1 { GameOutcome::Won } else { self.outcome_by_score() };
+            self.finish(outcome, &mut events);
+            return events;
+        }
+
+        if self.ticks_per_second() != ticks_per_second {
+            events.push(GameEvent::SpeedChanged { ticks_per_second: self.ticks_per_second() });
+        }
+
+        events
+    }
+
+    fn apply_food_effect(&mut self, index: usize, effect: FoodEffect, events: &mut Vec<GameEvent>) {
+        let snake = &mut self.snakes[index];
+        match effect {
+            FoodEffect::Grow(segments) => snake.grow(segments),
+            FoodEffect::Shrink(segments) => {
+                for position in snake.shrink(segments) {
+                    self.grid.set(&position, Cell::Empty);
                 }
-                _ => {}
+                events.push(GameEvent::Shrank { snake: index, length: snake.length() });
+            }
+            // the speed is shared, so it changes for every snake
+            FoodEffect::Speed { factor, ticks } => self.speed_effect = Some((factor, self.tick + ticks)),
+            FoodEffect::Poison => {
+                let head = *snake.head();
+                self.kill(index, DeathCause::Poison, events);
+                self.grid.set(&head, Cell::Empty);
+            }
+        }
+    }
+
+    // fixed timestep driver: runs as many ticks as `elapsed` covers at the current speed
+    pub fn advance(&mut self, elapsed: Duration, inputs: &[Input]) -> Vec<GameEvent> {
+        self.apply_inputs(inputs);
+        self.advance_with(elapsed, |_| Vec::new())
+    }
+
+    // like `advance`, but asks `tick_inputs` for the inputs of every tick right before it runs
+    pub fn advance_with(&mut self, elapsed: Duration, mut tick_inputs: impl FnMut(&Self) -> Vec<Input>) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.timer.accumulate(elapsed, self.ticks_per_second());
+        while !self.is_over() && self.timer.try_tick(self.ticks_per_second()) {
+            let inputs = tick_inputs(self);
+            events.extend(self.step(&inputs));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    fn advance_for(logic: &mut SnakeGameLogic, frames: usize) {
+        for _ in 0..frames {
+            logic.advance(Duration::from_millis(16), &[]);
+        }
+    }
+
+    #[test]
+    fn advance_steps_once_per_elapsed_tick() {
+        let mut stepped = SnakeGameLogic::new(GameConfig::default(), 3);
+        let mut advanced = SnakeGameLogic::new(GameConfig::default(), 3);
+
+        advanced.advance(Duration::from_millis(100), &[]);
+        assert_eq!(advanced.snakes[0].head(), stepped.snakes[0].head());
+
+        advanced.advance(Duration::from_millis(100), &[Input::Turn { snake: 0, direction: Direction::Up }]);
+        stepped.step(&[Input::Turn { snake: 0, direction: Direction::Up }]);
+        assert_ne!(stepped.snakes[0].head(), &math::Position::new(0, 0));
+        assert_eq!(advanced.snakes[0].head(), stepped.snakes[0].head());
+    }
+
+    #[test]
+    fn up_grows_y_at_the_configured_tick_rate() {
+        let config = GameConfig { speed: SpeedCurve::Constant { ticks_per_second: 10.0 }, ..GameConfig::default() };
+        let mut logic = SnakeGameLogic::new(config, 3);
+
+        logic.advance(Duration::from_millis(150), &[Input::Turn { snake: 0, direction: Direction::Up }]);
+        assert_eq!(logic.snakes[0].head(), &math::Position::new(0, 1));
+    }
+
+    #[test]
+    fn running_into_the_wall_or_itself_ends_the_game() {
+        let mut logic = SnakeGameLogic::new(GameConfig::default(), 3);
+        for _ in 0..14 {
+            logic.step(&[]);
+            assert!(!logic.is_over());
+        }
+        let outcome = GameOutcome::Died(DeathCause::Wall);
+        assert_eq!(logic.step(&[]), vec![GameEvent::Died { snake: 0, cause: DeathCause::Wall }, GameEvent::GameOver { outcome }]);
+        assert_eq!(logic.outcome(), Some(outcome));
+        assert!(logic.step(&[]).is_empty());
+
+        let mut logic = SnakeGameLogic::new(GameConfig::default(), 3);
+        logic.snakes[0].grow(4);
+        for direction in [Direction::Right, Direction::Right, Direction::Right, Direction::Right, Direction::Up, Direction::Left] {
+            logic.step(&[Input::Turn { snake: 0, direction }]);
+            assert!(!logic.is_over());
+        }
+        logic.step(&[Input::Turn { snake: 0, direction: Direction::Down }]);
+        assert_eq!(logic.outcome(), Some(GameOutcome::Died(DeathCause::SelfCollision)));
+    }
+
+    fn place_food(logic: &mut SnakeGameLogic, position: math::Position) {
+        for item in logic.food.items() {
+            logic.grid.set(&item.position, Cell::Empty);
+        }
+        logic.food = food::Food::new(1, &[position], &mut logic.grid, &mut logic.rng);
+    }
+
+    #[test]
+    fn food_only_spawns_on_free_cells() {
+        let mut rng = GameRng::seed_from_u64(5);
+        for seed in 0..50 {
+            let mut logic = SnakeGameLogic::new(GameConfig { board: Board::new(5, 5), ..GameConfig::default() }, seed);
+            while !logic.is_over() {
+                let direction = Direction::ALL[rng.gen_range(0..Direction::ALL.len())];
+                logic.step(&[Input::Turn { snake: 0, direction }]);
+                assert!(logic.food.positions().all(|food| !logic.snakes[0].occupies(food)));
             }
         }
     }
 
-    pub fn window_event(&mut self, event: &WindowEvent) -> Result<()> {
-        if let WindowEvent::KeyboardInput { device_id, event, is_synthetic } = event {
-            self.keyboard_event(device_id, event, *is_synthetic);
+    // the snake fills a 3 x 3 board in a spiral from the centre, finding food right ahead on every
+    // tick, with one extra segment so that its tail still covers the centre at the end
+    #[test]
+    fn filling_the_board_wins() {
+        let mut logic = SnakeGameLogic::new(GameConfig { board: Board::new(3, 3), ..GameConfig::default() }, 1);
+        logic.snakes[0].grow(1);
+        let turns = [Direction::Right, Direction::Down, Direction::Left, Direction::Left, Direction::Up, Direction::Up, Direction::Right, Direction::Right];
+
+        for (index, direction) in turns.iter().enumerate() {
+            let (head, offset) = (*logic.snakes[0].head(), direction.convert_to_position());
+            place_food(&mut logic, math::Position::new(head.x() + offset.x(), head.y() + offset.y()));
+
+            logic.step(&[Input::Turn { snake: 0, direction: *direction }]);
+            assert_eq!(logic.outcome(), (index == turns.len() - 1).then_some(GameOutcome::Won));
+        }
+        assert!(logic.food.is_empty());
+    }
+
+    #[test]
+    fn wrapping_edges_bring_the_snake_back_on_the_other_side() {
+        let config = GameConfig { edge_policy: EdgePolicy::Wrap, ..GameConfig::default() };
+        let mut logic = SnakeGameLogic::new(config, 3);
+        for _ in 0..15 {
+            logic.step(&[]);
+        }
+        assert_eq!(logic.snakes[0].head(), &math::Position::new(-15, 0));
+
+        // ten cells up from the middle row is one past the top edge, so the bottom row
+        for _ in 0..10 {
+            logic.step(&[Input::Turn { snake: 0, direction: Direction::Up }]);
+        }
+        assert!(!logic.is_over());
+        assert_eq!(logic.snakes[0].head(), &math::Position::new(-15, -10));
+    }
+
+    #[test]
+    fn slow_constant_speeds_still_tick() {
+        let config = GameConfig { speed: SpeedCurve::Constant { ticks_per_second: 3.0 }, ..GameConfig::default() };
+        let mut logic = SnakeGameLogic::new(config, 1);
+        advance_for(&mut logic, 100);
+
+        assert_eq!(logic.tick(), 4);
+    }
+
+    #[test]
+    fn slow_level_speeds_still_tick() {
+        let level = Level::parse("speed: 2\n\n.........\n.........\n.........\n").unwrap();
+        let mut logic = level.logic(1);
+        advance_for(&mut logic, 100);
+
+        assert_eq!(logic.tick(), 3);
+    }
+
+    #[test]
+    fn slow_down_food_wears_off_on_the_normal_curve() {
+        let config = GameConfig { edge_policy: EdgePolicy::Wrap, food_count: FoodCount::Fixed(0), ..GameConfig::default() };
+        let mut logic = SnakeGameLogic::new(config, 1);
+        let normal = logic.ticks_per_second();
+        logic.apply_food_effect(0, FoodKind::SlowDown.effect(), &mut Vec::new());
+        assert!(logic.ticks_per_second() < 4.0);
+
+        advance_for(&mut logic, 60);
+        assert!(logic.tick() >= 2, "stuck at tick {}", logic.tick());
+
+        advance_for(&mut logic, 2000);
+        assert!(logic.tick() > 50);
+        assert_eq!(logic.ticks_per_second(), normal);
+    }
+
+    #[test]
+    fn replays_play_back_at_slow_speeds() {
+        let config = GameConfig {
+            edge_policy: EdgePolicy::Wrap,
+            speed: SpeedCurve::Constant { ticks_per_second: 3.0 },
+            ..GameConfig::default()
+        };
+        let mut replay = Replay::new(1, config);
+        let mut logic = replay.start();
+        for _ in 0..10 {
+            logic.step(&[]);
         }
+        replay.finish(&logic);
 
-        if self.timer.should_update(self.player_snake.body().len() + 1) {
-            self.update();
+        let mut player = ReplayPlayer::new(replay);
+        let mut logic = player.rewind();
+        for _ in 0..100 {
+            player.advance(&mut logic, Duration::from_millis(16));
         }
+        assert_eq!(logic.tick(), 4);
+    }
+
+    #[test]
+    fn the_same_seed_and_inputs_play_the_same_game() {
+        let config = GameConfig { edge_policy: EdgePolicy::Wrap, food_count: FoodCount::Fixed(4), ..GameConfig::default() };
+        let mut first = SnakeGameLogic::new(config.clone(), 9);
+        let mut second = SnakeGameLogic::new(config, 9);
+        assert_eq!(first, second);
+
+        let mut rng = GameRng::seed_from_u64(5);
+        for _ in 0..500 {
+            let direction = Direction::ALL[rng.gen_range(0..Direction::ALL.len())];
+            let inputs = [Input::Turn { snake: 0, direction }];
+            assert_eq!(first.step(&inputs), second.step(&inputs));
+            assert_eq!(first, second);
+        }
+        assert!(first.tick() > 10);
+    }
 
-        let head = self.player_snake.head();
-        if head.x() < -self.width() / 2 || head.x() > self.width() / 2 || head.y() < -self.height() / 2 || head.y() > self.height() / 2 {
-            return Err(anyhow::anyhow!("Game Over"));
+    #[test]
+    fn events_alone_track_the_food_on_the_board() {
+        let config = GameConfig { edge_policy: EdgePolicy::Wrap, food_count: FoodCount::Fixed(6), ..GameConfig::default() };
+        let mut logic = SnakeGameLogic::new(config, 3);
+        let mut rng = GameRng::seed_from_u64(8);
+        let mut food = std::collections::HashSet::new();
+
+        let mut events = logic.spawn_events();
+        assert_eq!(events.len(), 6);
+        for _ in 0..500 {
+            for event in events {
+                match event {
+                    GameEvent::FoodSpawned { position, .. } => assert!(food.insert(position)),
+                    GameEvent::FoodExpired { position, .. } | GameEvent::AteFood { position, .. } => {
+                        assert!(food.remove(&position))
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(food, logic.food.positions().copied().collect());
+
+            let direction = Direction::ALL[rng.gen_range(0..Direction::ALL.len())];
+            events = logic.step(&[Input::Turn { snake: 0, direction }]);
         }
+    }
 
-        Ok(())
+    #[test]
+    fn a_full_board_with_several_snakes_goes_to_the_best_scorer_still_playing() {
+        let mut config = GameConfig::default();
+        config.set_players(3);
+        let mut logic = SnakeGameLogic::new(config, 1);
+        assert_eq!(logic.outcome_by_score(), GameOutcome::Draw);
+
+        logic.snakes[1].score_mut().award(10, 1, 1);
+        assert_eq!(logic.outcome_by_score(), GameOutcome::PlayerWon(1));
+
+        logic.kill(1, DeathCause::Wall, &mut Vec::new());
+        logic.snakes[2].score_mut().award(5, 1, 1);
+        assert_eq!(logic.outcome_by_score(), GameOutcome::PlayerWon(2));
+    }
+
+    #[test]
+    fn the_end_of_a_game_is_an_event() {
+        let spawns = vec![
+            (Position::new(-2, 0), Direction::Right),
+            (Position::new(2, 0), Direction::Left),
+            (Position::new(0, 3), Direction::Right),
+        ];
+        let config = GameConfig { edge_policy: EdgePolicy::Wrap, food_count: FoodCount::Fixed(0), spawns, ..GameConfig::default() };
+
+        let mut logic = SnakeGameLogic::new(config.clone(), 1);
+        logic.step(&[]);
+        let events = logic.step(&[]);
+        assert_eq!(events.last(), Some(&GameEvent::GameOver { outcome: GameOutcome::PlayerWon(2) }));
+        assert_eq!(logic.outcome(), Some(GameOutcome::PlayerWon(2)));
+
+        let config = GameConfig { spawns: config.spawns[..2].to_vec(), ..config };
+        let mut logic = SnakeGameLogic::new(config, 1);
+        logic.step(&[]);
+        assert_eq!(logic.step(&[]).last(), Some(&GameEvent::GameOver { outcome: GameOutcome::Draw }));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn filling_the_board_wins_without_food() {
+        for food in [0, 1] {
+            let config = GameConfig {
+                board: Board::new(1, 1),
+                edge_policy: EdgePolicy::Wrap,
+                food_count: FoodCount::Fixed(food),
+                ..GameConfig::default()
+            };
+            let mut logic = SnakeGameLogic::new(config, 1);
+            logic.step(&[]);
+            assert_eq!(logic.outcome(), Some(GameOutcome::Won));
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::VecDeque;
+
+use super::{Board, Cell, Controller, Direction, FoodEffect, FoodKind, Position, SnakeGameLogic};
+
+// a cell that can never be entered: obstacles and poison
+const NEVER: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutopilotMode {
+    // the shortest path to the nearest food that still leaves a way back to the tail, otherwise
+    // chases the tail to buy time
+    #[default]
+    Pathfinding,
+    // follows one fixed cycle through every cell, which fills the board without ever trapping
+    // itself. boards with obstacles or two odd sides have no such cycle and use pathfinding; so do
+    // the ticks where the cycle runs into poison or the snake is not on it yet
+    Hamiltonian,
+}
+
+impl AutopilotMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pathfinding" => Some(AutopilotMode::Pathfinding),
+            "hamiltonian" => Some(AutopilotMode::Hamiltonian),
+            _ => None,
+        }
+    }
+}
+
+// steers one snake by itself
+#[derive(Debug, Clone, Default)]
+pub struct Autopilot {
+    mode: AutopilotMode,
+    cycle: Option<Cycle>,
+}
+
+impl Autopilot {
+    pub fn new(mode: AutopilotMode) -> Self {
+        Self { mode, cycle: None }
+    }
+
+    pub fn mode(&self) -> AutopilotMode {
+        self.mode
+    }
+
+    fn follow_cycle(&mut self, logic: &SnakeGameLogic, snake: &VirtualSnake) -> Option<Direction> {
+        if !logic.obstacles().is_empty() {
+            return None;
+        }
+        if self.cycle.as_ref().is_none_or(|cycle| cycle.board != *logic.board()) {
+            self.cycle = Cycle::new(*logic.board());
+        }
+
+        let next = self.cycle.as_ref()?.next(&snake.head())?;
+        let direction = direction_to(logic, &snake.head(), &next)?;
+        let index = logic.board().index_of(&next)?;
+
+        (direction != snake.heading.opposite() && snake.free_in(logic)[index] <= 1).then_some(direction)
+    }
+}
+
+impl Controller for Autopilot {
+    fn decide(&mut self, logic: &SnakeGameLogic, snake: usize) -> Option<Direction> {
+        if logic.is_over() {
+            return None;
+        }
+
+        let snake = VirtualSnake::of(logic, snake);
+        let direction = match self.mode {
+            AutopilotMode::Pathfinding => pathfind(logic, &snake),
+            AutopilotMode::Hamiltonian => self.follow_cycle(logic, &snake).or_else(|| pathfind(logic, &snake)),
+        }?;
+
+        (direction != snake.heading).then_some(direction)
+    }
+}
+
+fn pathfind(logic: &SnakeGameLogic, snake: &VirtualSnake) -> Option<Direction> {
+    let edible = |position: &Position| matches!(logic.grid().get(position), Some(Cell::Food(kind)) if kind != FoodKind::Poison);
+
+    if let Some(path) = search(logic, snake, edible).path {
+        let mut planned = snake.clone();
+        for position in &path {
+            planned.advance(logic, *position);
+        }
+
+        if planned.can_reach_tail(logic) {
+            return direction_to(logic, &snake.head(), &path[0]);
+        }
+    }
+
+    chase_tail(logic, snake)
+}
+
+// no food is safe to go for: takes the move that keeps the tail reachable by the longest route, or
+// failing that the one with the most room left
+fn chase_tail(logic: &SnakeGameLogic, snake: &VirtualSnake) -> Option<Direction> {
+    let free_in = snake.free_in(logic);
+
+    Direction::ALL
+        .into_iter()
+        .filter(|direction| *direction != snake.heading.opposite())
+        .filter_map(|direction| {
+            let next = logic.neighbour(&snake.head(), direction);
+            let index = logic.board().index_of(&next)?;
+            if free_in[index] > 1 {
+                return None;
+            }
+
+            let mut moved = snake.clone();
+            moved.advance(logic, next);
+            let tail = moved.tail();
+            let rank = match search(logic, &moved, |position| *position == tail).path {
+                _ if moved.segments.len() == 1 => (true, 0),
+                Some(path) => (true, path.len()),
+                None => (false, search(logic, &moved, |_| false).reached),
+            };
+
+            Some((rank, direction))
+        })
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, direction)| direction)
+}
+
+fn direction_to(logic: &SnakeGameLogic, from: &Position, to: &Position) -> Option<Direction> {
+    Direction::ALL.into_iter().find(|direction| logic.neighbour(from, *direction) == *to)
+}
+
+// the snake as it would be after some planned moves
+#[derive(Debug, Clone)]
+struct VirtualSnake {
+    index: usize,
+    // head first
+    segments: VecDeque<Position>,
+    growth: usize,
+    heading: Direction,
+}
+
+impl VirtualSnake {
+    fn of(logic: &SnakeGameLogic, index: usize) -> Self {
+        let snake = &logic.snakes()[index];
+        let mut segments = snake.body().clone();
+        segments.push_front(*snake.head());
+
+        Self { index, segments, growth: snake.pending_growth(), heading: snake.heading() }
+    }
+
+    fn head(&self) -> Position {
+        self.segments[0]
+    }
+
+    fn tail(&self) -> Position {
+        self.segments[self.segments.len() - 1]
+    }
+
+    fn advance(&mut self, logic: &SnakeGameLogic, to: Position) {
+        if let Some(direction) = direction_to(logic, &self.head(), &to) {
+            self.heading = direction;
+        }
+
+        self.segments.push_front(to);
+        if self.growth > 0 {
+            self.growth -= 1;
+        } else {
+            self.segments.pop_back();
+        }
+
+        if let Some(Cell::Food(kind)) = logic.grid().get(&to) {
+            if let FoodEffect::Grow(segments) = kind.effect() {
+                self.growth += segments;
+            }
+        }
+    }
+
+    // for every board cell, how many ticks until the head may move into it
+    fn free_in(&self, logic: &SnakeGameLogic) -> Vec<u32> {
+        let board = logic.board();
+        let mut free_in: Vec<u32> = board
+            .cells()
+            .map(|position| match logic.grid().get(&position) {
+                Some(Cell::Obstacle | Cell::Food(FoodKind::Poison)) => NEVER,
+                _ => 0,
+            })
+            .collect();
+
+        // a segment frees up once every segment behind it and any growth have passed. the other
+        // snakes are taken to keep going the same way, which is all that can be known about them
+        let others = logic
+            .snakes()
+            .iter()
+            .enumerate()
+            .filter(|(index, snake)| *index != self.index && snake.is_alive())
+            .map(|(_, snake)| (std::iter::once(snake.head()).chain(snake.body()).copied().collect(), snake.pending_growth()));
+        for (segments, growth) in others.chain(std::iter::once((self.segments.clone(), self.growth))) {
+            let length = segments.len();
+            for (order, segment) in segments.iter().enumerate() {
+                if let Some(index) = board.index_of(segment) {
+                    free_in[index] = (length - order + growth) as u32;
+                }
+            }
+        }
+
+        free_in
+    }
+
+    fn can_reach_tail(&self, logic: &SnakeGameLogic) -> bool {
+        let tail = self.tail();
+        self.segments.len() == 1 || search(logic, self, |position| *position == tail).path.is_some()
+    }
+}
+
+struct Search {
+    // the cells from the head to the goal, head excluded
+    path: Option<Vec<Position>>,
+    // how many cells were reached before the search stopped
+    reached: usize,
+}
+
+// breadth first search from the head that only enters cells free by the time the head gets there
+fn search(logic: &SnakeGameLogic, snake: &VirtualSnake, goal: impl Fn(&Position) -> bool) -> Search {
+    let board = logic.board();
+    let free_in = snake.free_in(logic);
+    let Some(start) = board.index_of(&snake.head()) else {
+        return Search { path: None, reached: 0 };
+    };
+
+    let mut parent = vec![usize::MAX; board.cell_count()];
+    let mut distance = vec![NEVER; board.cell_count()];
+    distance[start] = 0;
+
+    let mut queue = VecDeque::from([start]);
+    let mut reached = 0;
+    while let Some(index) = queue.pop_front() {
+        let position = board.position_at(index).unwrap_or(snake.head());
+
+        for direction in Direction::ALL {
+            // the snake can not turn back on the very first move
+            if index == start && direction == snake.heading.opposite() {
+                continue;
+            }
+
+            let next_position = logic.neighbour(&position, direction);
+            let Some(next) = board.index_of(&next_position) else {
+                continue;
+            };
+            if distance[next] != NEVER || free_in[next] > distance[index] + 1 {
+                continue;
+            }
+
+            distance[next] = distance[index] + 1;
+            parent[next] = index;
+            reached += 1;
+
+            if goal(&next_position) {
+                let mut path = vec![next_position];
+                let mut current = index;
+                while current != start {
+                    path.extend(board.position_at(current));
+                    current = parent[current];
+                }
+                path.reverse();
+
+                return Search { path: Some(path), reached };
+            }
+
+            queue.push_back(next);
+        }
+    }
+
+    Search { path: None, reached }
+}
+
+// a closed tour through every cell of a board, only moving between orthogonal neighbours
+#[derive(Debug, Clone)]
+struct Cycle {
+    board: Board,
+    // position in the tour for every board cell, and the cells in tour order
+    order: Vec<usize>,
+    cells: Vec<Position>,
+}
+
+impl Cycle {
+    // sweeps back and forth over every column but the first, then returns along the first one.
+    // that needs an even number of rows, so boards with an odd number are swept sideways instead
+    fn new(board: Board) -> Option<Self> {
+        let (width, height) = (board.width(), board.height());
+        let transposed = height % 2 != 0;
+        let (columns, rows) = if transposed { (height, width) } else { (width, height) };
+        if columns < 2 || rows < 2 || rows % 2 != 0 {
+            return None;
+        }
+
+        let mut tour = Vec::with_capacity(board.cell_count());
+        for row in 0..rows {
+            if row % 2 == 0 {
+                tour.extend((1..columns).map(|column| (column, row)));
+            } else {
+                tour.extend((1..columns).rev().map(|column| (column, row)));
+            }
+        }
+        tour.extend((0..rows).rev().map(|row| (0, row)));
+
+        let cells: Vec<Position> = tour
+            .into_iter()
+            .map(|(column, row)| if transposed { (row, column) } else { (column, row) })
+            .map(|offset| board.origin() + Position::from(offset))
+            .collect();
+
+        let mut order = vec![0; board.cell_count()];
+        for (step, position) in cells.iter().enumerate() {
+            order[board.index_of(position)?] = step;
+        }
+
+        Some(Self { board, order, cells })
+    }
+
+    fn next(&self, position: &Position) -> Option<Position> {
+        let step = self.order[self.board.index_of(position)?];
+        Some(self.cells[(step + 1) % self.cells.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{EdgePolicy, FoodCount, GameConfig, GameOutcome, Input};
+
+    fn play(logic: &mut SnakeGameLogic, autopilot: &mut Autopilot, ticks: u64) {
+        while !logic.is_over() && logic.tick() < ticks {
+            let inputs: Vec<Input> = autopilot.decide(logic, 0).map(|direction| Input::Turn { snake: 0, direction }).into_iter().collect();
+            logic.step(&inputs);
+        }
+    }
+
+    #[test]
+    fn the_cycle_visits_every_cell_once() {
+        for (width, height) in [(6, 4), (5, 4), (4, 5), (2, 2)] {
+            let board = Board::new(width, height);
+            let cycle = Cycle::new(board).unwrap();
+
+            let mut position = board.origin();
+            let mut seen = vec![false; board.cell_count()];
+            for _ in 0..board.cell_count() {
+                let index = board.index_of(&position).unwrap();
+                assert!(!seen[index], "{}x{} visits {:?} twice", width, height, position);
+                seen[index] = true;
+
+                let next = cycle.next(&position).unwrap();
+                assert_eq!(position.manhattan_distance(&next), 1);
+                position = next;
+            }
+            assert_eq!(position, board.origin());
+        }
+
+        assert!(Cycle::new(Board::new(5, 5)).is_none());
+        assert!(Cycle::new(Board::new(1, 4)).is_none());
+    }
+
+    // poison that lands on the cycle right ahead of a long snake can leave it nowhere to go, which
+    // is the only way it may lose
+    #[test]
+    fn the_hamiltonian_autopilot_fills_the_board_unless_poison_blocks_it() {
+        let mut wins = 0;
+        for seed in 0..10 {
+            let config = GameConfig { board: Board::new(6, 4), ..GameConfig::default() };
+            let mut logic = SnakeGameLogic::new(config, seed);
+            let mut autopilot = Autopilot::new(AutopilotMode::Hamiltonian);
+
+            let mut blocked = false;
+            while !logic.is_over() {
+                let head = *logic.snakes()[0].head();
+                blocked = head.neighbours().any(|cell| logic.grid().get(&cell) == Some(Cell::Food(FoodKind::Poison)));
+                let next = logic.tick() + 1;
+                play(&mut logic, &mut autopilot, next);
+            }
+
+            match logic.outcome() {
+                Some(GameOutcome::Won) => wins += 1,
+                outcome => assert!(blocked, "seed {} ended with {:?} at tick {}", seed, outcome, logic.tick()),
+            }
+        }
+        assert!(wins >= 5, "only {} of 10 games won", wins);
+    }
+
+    #[test]
+    fn pathfinding_takes_the_shortest_way_to_food() {
+        let food = Position::new(3, -4);
+        let config = GameConfig {
+            edge_policy: EdgePolicy::Walls,
+            obstacles: vec![Position::new(1, 0), Position::new(1, -1)],
+            initial_food: vec![food],
+            food_count: FoodCount::Fixed(1),
+            ..GameConfig::default()
+        };
+        let mut logic = SnakeGameLogic::new(config, 1);
+        let mut autopilot = Autopilot::new(AutopilotMode::Pathfinding);
+
+        while logic.tick() < 7 {
+            let inputs: Vec<Input> = autopilot.decide(&logic, 0).map(|direction| Input::Turn { snake: 0, direction }).into_iter().collect();
+            logic.step(&inputs);
+        }
+        assert_eq!(logic.snakes()[0].head(), &food);
+        assert!(logic.snakes()[0].score().points() > 0);
+    }
+
+    #[test]
+    fn pathfinding_turns_away_from_walls() {
+        let board = Board::new(6, 4);
+        let config = GameConfig { board, spawns: vec![(board.bounds().max(), Direction::Right)], food_count: FoodCount::Fixed(0), ..GameConfig::default() };
+        let logic = SnakeGameLogic::new(config, 1);
+
+        assert!(matches!(Autopilot::default().decide(&logic, 0), Some(Direction::Down | Direction::Left)));
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use super::math::{Position, Rect};
+
+// the playing field: exactly `width` x `height` cells, the lowest one at `origin`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Board {
+    width: i32,
+    height: i32,
+    origin: Position,
+}
+
+impl Board {
+    // a board centred on (0, 0); with an even size the extra column or row goes to the negative side
+    pub fn new(width: i32, height: i32) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        Self::with_origin(width, height, Position::new(-(width / 2), -(height / 2)))
+    }
+
+    pub fn with_origin(width: i32, height: i32, origin: Position) -> Self {
+        Self { width: width.max(1), height: height.max(1), origin }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.origin, self.width, self.height)
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.bounds().area()
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.bounds().contains(position)
+    }
+
+    // row-major from the bottom-left cell; `None` when the position is off the board
+    pub fn index_of(&self, position: &Position) -> Option<usize> {
+        self.bounds().index_of(position)
+    }
+
+    pub fn position_at(&self, index: usize) -> Option<Position> {
+        self.bounds().position_at(index)
+    }
+
+    // `column` counts from the left edge and `row` from the top edge, like a text grid
+    pub fn position_from_top_left(&self, column: i32, row: i32) -> Position {
+        self.origin + Position::new(column, self.height - 1 - row)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Position> {
+        self.bounds().cells()
+    }
+
+    pub fn wrap(&self, position: Position) -> Position {
+        self.bounds().wrap(position)
+    }
+
+    // where a cell sits relative to the middle of the board, in cells; used to centre the board on screen
+    pub fn offset_from_centre(&self, position: &Position) -> [f32; 2] {
+        let offset = *position - self.origin;
+        [
+            offset.x() as f32 - (self.width - 1) as f32 / 2.0,
+            offset.y() as f32 - (self.height - 1) as f32 / 2.0,
+        ]
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(30, 20)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odd_and_even_boards_have_exactly_their_cells() {
+        for (width, height) in [(30, 20), (5, 5), (4, 7), (1, 1)] {
+            let board = Board::new(width, height);
+            let cells: Vec<Position> = board.cells().collect();
+            assert_eq!(cells.len(), (width * height) as usize);
+            assert_eq!(board.cell_count(), cells.len());
+
+            for (index, cell) in cells.iter().enumerate() {
+                assert!(board.contains(cell));
+                assert_eq!(board.index_of(cell), Some(index));
+                assert_eq!(board.position_at(index), Some(*cell));
+            }
+            assert_eq!(board.position_at(cells.len()), None);
+            assert_eq!(board.index_of(&(board.origin() - Position::new(1, 0))), None);
+            assert_eq!(board.index_of(&(board.origin() + Position::new(0, height))), None);
+        }
+    }
+
+    #[test]
+    fn the_board_is_centred_with_the_top_left_cell_first_in_text() {
+        let board = Board::new(4, 3);
+        assert_eq!(board.origin(), Position::new(-2, -1));
+        assert_eq!(board.position_from_top_left(0, 0), Position::new(-2, 1));
+        assert_eq!(board.position_from_top_left(3, 2), Position::new(1, -1));
+
+        assert_eq!(board.offset_from_centre(&board.position_from_top_left(0, 0)), [-1.5, 1.0]);
+        assert_eq!(board.offset_from_centre(&board.position_from_top_left(3, 2)), [1.5, -1.0]);
+    }
+}
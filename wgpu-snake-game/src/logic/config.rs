@@ -0,0 +1,182 @@
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use super::{math::Position, Board, Direction, SpeedCurve};
+
+// cells in front of a spread out spawn that are kept free of obstacles
+const SPAWN_CLEARANCE: i32 = 4;
+
+// where the snake starts when no spawn is given
+const CENTRE_SPAWN: [(Position, Direction); 1] = [(Position::ZERO, Direction::Right)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EdgePolicy {
+    #[default]
+    Walls,
+    Wrap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FoodCount {
+    Fixed(usize),
+    // one item per `cells_per_food` cells of the board, at least one
+    Scaled { cells_per_food: usize },
+}
+
+impl Default for FoodCount {
+    fn default() -> Self {
+        FoodCount::Fixed(1)
+    }
+}
+
+impl FoodCount {
+    pub fn count(&self, board: &Board) -> usize {
+        match self {
+            FoodCount::Fixed(count) => *count,
+            FoodCount::Scaled { cells_per_food } => (board.cell_count() / (*cells_per_food).max(1)).max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub board: Board,
+    pub edge_policy: EdgePolicy,
+    pub obstacles: Vec<Position>,
+    pub speed: SpeedCurve,
+    // where each snake starts and which way it first moves; one snake per entry, or a single snake in
+    // the centre heading right when empty
+    pub spawns: Vec<(Position, Direction)>,
+    pub initial_food: Vec<Position>,
+    pub food_count: FoodCount,
+}
+
+impl GameConfig {
+    // the spawns the game starts from, with the centre spawn filled in
+    pub fn spawns(&self) -> &[(Position, Direction)] {
+        if self.spawns.is_empty() { &CENTRE_SPAWN } else { &self.spawns }
+    }
+
+    // keeps the first `players` spawns, adding new ones spread over the board if there are too few
+    pub fn set_players(&mut self, players: usize) {
+        let players = players.max(1);
+        if self.spawns.len() >= players {
+            self.spawns.truncate(players);
+            return;
+        }
+        if players == 1 {
+            return;
+        }
+
+        // one horizontal band per snake, alternately starting on the left heading right and on the
+        // right heading left, moved up a row at a time until the first cells ahead are clear
+        let (width, height) = (self.board.width(), self.board.height());
+        for player in self.spawns.len() as i32..players as i32 {
+            let (column, direction) = match player % 2 {
+                0 => (width / 4, Direction::Right),
+                _ => (width - 1 - width / 4, Direction::Left),
+            };
+            let band = height * (2 * player + 1) / (2 * players as i32);
+            let row = (0..height)
+                .map(|offset| (band + offset) % height)
+                .find(|row| self.is_clear_ahead(self.board.origin() + Position::new(column, *row), direction))
+                .unwrap_or(band);
+
+            self.spawns.push((self.board.origin() + Position::new(column, row), direction));
+        }
+    }
+
+    // spawns and starting food must sit on free cells of the board, which a level checks as it is
+    // parsed but a resized board or a new layout can break
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let spawns = self.spawns();
+        for (index, (spawn, _)) in spawns.iter().enumerate() {
+            if !self.board.contains(spawn) {
+                bail!("spawn {} {} is outside the {}x{} board", spawn.x(), spawn.y(), self.board.width(), self.board.height());
+            }
+            if self.obstacles.contains(spawn) {
+                bail!("spawn {} {} is on an obstacle", spawn.x(), spawn.y());
+            }
+            if spawns[..index].iter().any(|(other, _)| other == spawn) {
+                bail!("two snakes spawn on {} {}", spawn.x(), spawn.y());
+            }
+        }
+
+        for (index, food) in self.initial_food.iter().enumerate() {
+            if !self.board.contains(food) || self.obstacles.contains(food) || spawns.iter().any(|(spawn, _)| spawn == food) {
+                bail!("food {} {} is not on a free cell of the board", food.x(), food.y());
+            }
+            if self.initial_food[..index].contains(food) {
+                bail!("two foods are placed on {} {}", food.x(), food.y());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_clear_ahead(&self, position: Position, direction: Direction) -> bool {
+        (0..SPAWN_CLEARANCE)
+            .map(|distance| position + direction.convert_to_position() * distance)
+            .all(|cell| {
+                self.board.contains(&cell)
+                    && !self.obstacles.contains(&cell)
+                    && !self.spawns.iter().any(|(spawn, _)| *spawn == cell)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::Level;
+
+    #[test]
+    fn set_players_keeps_the_spawns_it_was_given() {
+        let mut config = Level::parse("spawn: 1 1 right\nspawn: 10 3 left\n\n............\n............\n............\n............\n")
+            .unwrap()
+            .config;
+        let authored = config.spawns.clone();
+
+        config.set_players(4);
+        assert_eq!(config.spawns.len(), 4);
+        assert_eq!(config.spawns[..2], authored[..]);
+        for (index, (spawn, _)) in config.spawns.iter().enumerate() {
+            assert!(config.board.contains(spawn));
+            assert!(!config.spawns[..index].iter().any(|(other, _)| other == spawn));
+        }
+
+        config.set_players(1);
+        assert_eq!(config.spawns, authored[..1]);
+    }
+
+    #[test]
+    fn validate_rejects_spawns_off_free_cells() {
+        let mut config = GameConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.obstacles = vec![Position::ZERO];
+        assert_eq!(config.validate().unwrap_err().to_string(), "spawn 0 0 is on an obstacle");
+
+        config.obstacles.clear();
+        config.board = Board::with_origin(4, 4, Position::new(1, 1));
+        assert_eq!(config.validate().unwrap_err().to_string(), "spawn 0 0 is outside the 4x4 board");
+    }
+
+    #[test]
+    fn set_players_spreads_snakes_without_spawns() {
+        let mut config = GameConfig::default();
+        config.set_players(1);
+        assert_eq!(config.spawns(), CENTRE_SPAWN);
+
+        config.set_players(2);
+        let (width, height) = (config.board.width(), config.board.height());
+        let origin = config.board.origin();
+        assert_eq!(
+            config.spawns,
+            [
+                (origin + Position::new(width / 4, height / 4), Direction::Right),
+                (origin + Position::new(width - 1 - width / 4, height * 3 / 4), Direction::Left),
+            ]
+        );
+    }
+}
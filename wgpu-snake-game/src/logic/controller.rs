@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+
+use rand::{seq::SliceRandom, SeedableRng};
+
+use super::{Autopilot, AutopilotMode, Cell, Direction, FoodKind, GameRng, Position, SnakeGameLogic};
+
+// anything that can steer a snake: a player, a bot or a script. it is asked once per tick, right
+// before the tick runs, for as long as its snake is alive, and may only look at the game
+pub trait Controller {
+    // the turn for snake `snake` to make, `None` to keep going
+    fn decide(&mut self, logic: &SnakeGameLogic, snake: usize) -> Option<Direction>;
+}
+
+// the built-in controllers by name: straight, random, greedy, pathfinding or hamiltonian. `seed`
+// feeds the ones that make random choices
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn Controller>> {
+    match name {
+        "straight" => Some(Box::new(ScriptedController::default())),
+        "random" => Some(Box::new(RandomController::new(seed))),
+        "greedy" => Some(Box::new(GreedyController)),
+        other => AutopilotMode::from_name(other).map(|mode| Box::new(Autopilot::new(mode)) as Box<dyn Controller>),
+    }
+}
+
+// turns on fixed ticks, for tests and reproducing bugs; with no turns the snake goes straight
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedController {
+    // the tick each turn is made right before, in order
+    turns: VecDeque<(u64, Direction)>,
+}
+
+impl ScriptedController {
+    pub fn new(turns: impl IntoIterator<Item = (u64, Direction)>) -> Self {
+        let mut turns: Vec<_> = turns.into_iter().collect();
+        turns.sort_by_key(|(tick, _)| *tick);
+
+        Self { turns: turns.into() }
+    }
+
+    // one entry per tick starting with the first, `None` going straight
+    pub fn from_sequence(sequence: impl IntoIterator<Item = Option<Direction>>) -> Self {
+        let turns = sequence
+            .into_iter()
+            .zip(1..)
+            .filter_map(|(direction, tick)| direction.map(|direction| (tick, direction)));
+
+        Self::new(turns)
+    }
+}
+
+impl Controller for ScriptedController {
+    fn decide(&mut self, logic: &SnakeGameLogic, _snake: usize) -> Option<Direction> {
+        let tick = logic.tick() + 1;
+        while self.turns.front().is_some_and(|(turn_tick, _)| *turn_tick < tick) {
+            self.turns.pop_front();
+        }
+
+        match self.turns.front() {
+            Some(&(turn_tick, direction)) if turn_tick == tick => {
+                self.turns.pop_front();
+                Some(direction)
+            }
+            _ => None,
+        }
+    }
+}
+
+// a random direction that does not die on the next tick
+#[derive(Debug, Clone)]
+pub struct RandomController {
+    rng: GameRng,
+}
+
+impl RandomController {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: GameRng::seed_from_u64(seed) }
+    }
+}
+
+impl Controller for RandomController {
+    fn decide(&mut self, logic: &SnakeGameLogic, snake: usize) -> Option<Direction> {
+        safe_directions(logic, snake).choose(&mut self.rng).copied()
+    }
+}
+
+// the safe direction that gets closest to the nearest edible food, without looking further ahead
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedyController;
+
+impl Controller for GreedyController {
+    fn decide(&mut self, logic: &SnakeGameLogic, snake: usize) -> Option<Direction> {
+        let safe = safe_directions(logic, snake);
+        let snake = &logic.snakes()[snake];
+        let target = logic
+            .food
+            .items()
+            .iter()
+            .filter(|item| item.kind != FoodKind::Poison)
+            .map(|item| item.position)
+            .min_by_key(|position| position.manhattan_distance(snake.head()));
+
+        // with nothing worth eating, just stay alive
+        let Some(target) = target else {
+            return safe.first().copied().filter(|_| !safe.contains(&snake.heading()));
+        };
+
+        safe.into_iter()
+            .min_by_key(|direction| logic.neighbour(snake.head(), *direction).manhattan_distance(&target))
+    }
+}
+
+// the directions snake `snake` can take without dying on the next tick, as far as it can tell
+// without knowing where the other snakes go
+fn safe_directions(logic: &SnakeGameLogic, snake: usize) -> Vec<Direction> {
+    let snake = &logic.snakes()[snake];
+    let is_safe = |position: &Position| match logic.grid().get(position) {
+        Some(Cell::Empty) => true,
+        Some(Cell::Food(kind)) => kind != FoodKind::Poison,
+        _ => false,
+    };
+
+    Direction::ALL
+        .into_iter()
+        .filter(|direction| *direction != snake.heading().opposite())
+        .filter(|direction| is_safe(&logic.neighbour(snake.head(), *direction)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{GameConfig, Input};
+
+    fn play(logic: &mut SnakeGameLogic, controller: &mut dyn Controller, ticks: u64) -> Vec<Option<Direction>> {
+        let mut decisions = Vec::new();
+        for _ in 0..ticks {
+            let decision = controller.decide(logic, 0);
+            logic.step(&decision.map(|direction| Input::Turn { snake: 0, direction }).into_iter().collect::<Vec<_>>());
+            decisions.push(decision);
+        }
+        decisions
+    }
+
+    #[test]
+    fn a_script_turns_right_before_its_ticks() {
+        let mut logic = SnakeGameLogic::new(GameConfig::default(), 1);
+        let mut script = ScriptedController::from_sequence([None, Some(Direction::Up), None, Some(Direction::Left)]);
+
+        let decisions = play(&mut logic, &mut script, 5);
+        assert_eq!(decisions, [None, Some(Direction::Up), None, Some(Direction::Left), None]);
+        assert_eq!(logic.snakes()[0].head(), &Position::new(-1, 2));
+
+        let mut script = ScriptedController::new([(3, Direction::Down), (1, Direction::Up)]);
+        let decisions = play(&mut SnakeGameLogic::new(GameConfig::default(), 1), &mut script, 3);
+        assert_eq!(decisions, [Some(Direction::Up), None, Some(Direction::Down)]);
+    }
+
+    #[test]
+    fn bots_only_pick_safe_directions() {
+        for name in ["random", "greedy"] {
+            for seed in 0..10 {
+                let mut logic = SnakeGameLogic::new(GameConfig::default(), seed);
+                let mut controller = by_name(name, seed).unwrap();
+                while !logic.is_over() && logic.tick() < 300 {
+                    let decision = controller.decide(&logic, 0);
+                    if let Some(direction) = decision {
+                        assert!(safe_directions(&logic, 0).contains(&direction), "{} turned {:?} into danger", name, direction);
+                    }
+                    logic.step(&decision.map(|direction| Input::Turn { snake: 0, direction }).into_iter().collect::<Vec<_>>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn knows_the_built_in_controllers() {
+        for name in ["straight", "random", "greedy", "pathfinding", "hamiltonian"] {
+            assert!(by_name(name, 0).is_some(), "{} is missing", name);
+        }
+        assert!(by_name("clever", 0).is_none());
+    }
+}
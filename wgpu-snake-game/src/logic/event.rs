@@ -0,0 +1,14 @@
+use super::{math::Position, DeathCause, FoodKind, GameOutcome};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    AteFood { snake: usize, kind: FoodKind, position: Position, points: u64 },
+    Grew { snake: usize, length: usize },
+    Shrank { snake: usize, length: usize },
+    SpeedChanged { ticks_per_second: f32 },
+    FoodSpawned { kind: FoodKind, position: Position },
+    FoodExpired { kind: FoodKind, position: Position },
+    Died { snake: usize, cause: DeathCause },
+    // the last event of a game, however it ended
+    GameOver { outcome: GameOutcome },
+}
@@ -1,46 +1,166 @@
-use rand::{rngs::ThreadRng, Rng};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
-use super::math::Position;
+use super::{math::Position, occupancy::{Cell, OccupancyGrid}};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FoodKind {
+    Regular,
+    Golden,
+    Shrinking,
+    SpeedUp,
+    SlowDown,
+    Poison,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FoodEffect {
+    Grow(usize),
+    Shrink(usize),
+    // scales the tick rate by `factor` for the next `ticks` ticks
+    Speed { factor: f32, ticks: u64 },
+    Poison,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 6] = [
+        FoodKind::Regular,
+        FoodKind::Golden,
+        FoodKind::Shrinking,
+        FoodKind::SpeedUp,
+        FoodKind::SlowDown,
+        FoodKind::Poison,
+    ];
+
+    pub fn effect(&self) -> FoodEffect {
+        match self {
+            FoodKind::Regular => FoodEffect::Grow(1),
+            FoodKind::Golden => FoodEffect::Grow(1),
+            FoodKind::Shrinking => FoodEffect::Shrink(3),
+            FoodKind::SpeedUp => FoodEffect::Speed { factor: 1.5, ticks: 50 },
+            FoodKind::SlowDown => FoodEffect::Speed { factor: 0.6, ticks: 50 },
+            FoodKind::Poison => FoodEffect::Poison,
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        match self {
+            FoodKind::Regular => 10,
+            FoodKind::Golden => 50,
+            FoodKind::Shrinking => 5,
+            FoodKind::SpeedUp => 15,
+            FoodKind::SlowDown => 5,
+            FoodKind::Poison => 0,
+        }
+    }
+
+    pub fn spawn_weight(&self) -> u32 {
+        match self {
+            FoodKind::Regular => 70,
+            FoodKind::Golden => 8,
+            FoodKind::Shrinking => 7,
+            FoodKind::SpeedUp => 6,
+            FoodKind::SlowDown => 6,
+            FoodKind::Poison => 3,
+        }
+    }
+
+    // number of ticks the food stays on the board, forever if `None`
+    pub fn lifetime(&self) -> Option<u64> {
+        match self {
+            FoodKind::Regular => None,
+            FoodKind::Golden => Some(40),
+            FoodKind::Shrinking | FoodKind::SpeedUp | FoodKind::SlowDown => Some(60),
+            FoodKind::Poison => Some(50),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FoodItem {
+    pub position: Position,
+    pub kind: FoodKind,
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Food {
-    food_positions: Vec<Position>,
-    thread_rng: ThreadRng,
+    food_items: Vec<FoodItem>,
 }
 
 impl Food {
-    pub fn new(width: i32, height: i32) -> Self {
-        let thread_rng = rand::thread_rng();
-        let food_positions = Vec::with_capacity(1);
+    // places the regular `initial` items, then random ones until `count` are on the board
+    pub fn new(count: usize, initial: &[Position], grid: &mut OccupancyGrid, rng: &mut impl Rng) -> Self {
+        let mut food_items = Vec::with_capacity(count.max(initial.len()));
+        for &position in initial {
+            grid.set(&position, Cell::Food(FoodKind::Regular));
+            food_items.push(FoodItem { position, kind: FoodKind::Regular, expires_at: None });
+        }
 
         let mut food = Self {
-            food_positions,
-            thread_rng,
+            food_items,
         };
 
-        food.spawn_food(width, height);
+        food.refill(count, grid, 0, rng);
 
         food
     }
 
-    pub fn positions(&self) -> &[Position] {
-        &self.food_positions
+    pub fn items(&self) -> &[FoodItem] {
+        &self.food_items
     }
 
-    pub fn try_eat_food(&mut self, position: &Position, width: i32, height: i32) -> bool {
-        if self.food_positions.iter().any(|food| food == position) {
-            self.food_positions.clear();
-            self.spawn_food(width, height);
-            true
-        } else {
-            false
-        }
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.food_items.iter().map(|item| &item.position)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.food_items.is_empty()
     }
 
+    pub fn eat(&mut self, position: &Position) -> Option<FoodItem> {
+        let index = self.food_items.iter().position(|item| &item.position == position)?;
+        Some(self.food_items.swap_remove(index))
+    }
+
+    // removes and returns every item whose lifetime ran out
+    pub fn expire(&mut self, grid: &mut OccupancyGrid, tick: u64) -> Vec<FoodItem> {
+        let mut expired = Vec::new();
+        self.food_items.retain(|item| {
+            let alive = item.expires_at.is_none_or(|expires_at| tick < expires_at);
+            if !alive {
+                grid.set(&item.position, Cell::Empty);
+                expired.push(*item);
+            }
+            alive
+        });
+
+        expired
+    }
 
-    fn spawn_food(&mut self, width: i32, height: i32) {
-        let x = self.thread_rng.gen_range(-width / 2..=width / 2);
-        let y = self.thread_rng.gen_range(-height / 2..=height / 2);
+    // spawns items on free cells until `count` are on the board or none is left, returning the new ones
+    pub fn refill(&mut self, count: usize, grid: &mut OccupancyGrid, tick: u64, rng: &mut impl Rng) -> Vec<FoodItem> {
+        let mut spawned = Vec::new();
+        while self.food_items.len() < count {
+            let Some(position) = grid.random_free(rng) else {
+                break;
+            };
 
-        self.food_positions.push(Position::new(x, y));
+            let kind = FoodKind::ALL
+                .choose_weighted(rng, |kind| kind.spawn_weight())
+                .copied()
+                .unwrap_or(FoodKind::Regular);
+
+            let item = FoodItem {
+                position,
+                kind,
+                expires_at: kind.lifetime().map(|lifetime| tick + lifetime),
+            };
+            grid.set(&position, Cell::Food(kind));
+            self.food_items.push(item);
+            spawned.push(item);
+        }
+
+        spawned
     }
-}
\ No newline at end of file
+}
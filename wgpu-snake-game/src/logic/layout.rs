@@ -0,0 +1,46 @@
+use super::{math::{Position, Rect}, Board};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    #[default]
+    Empty,
+    Border,
+    Pillars,
+}
+
+impl Layout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "empty" => Some(Layout::Empty),
+            "border" => Some(Layout::Border),
+            "pillars" => Some(Layout::Pillars),
+            _ => None,
+        }
+    }
+
+    pub fn obstacles(&self, board: &Board) -> Vec<Position> {
+        let bounds = board.bounds();
+        let (min, max) = (bounds.min(), bounds.max());
+
+        match self {
+            Layout::Empty => Vec::new(),
+            Layout::Border => bounds
+                .cells()
+                .filter(|position| position.x() == min.x() || position.x() == max.x() || position.y() == min.y() || position.y() == max.y())
+                .collect(),
+            Layout::Pillars => {
+                let centre = min + Position::new(bounds.width() / 2, bounds.height() / 2);
+                let (pillar_x, pillar_y) = (bounds.width() / 4, bounds.height() / 4);
+
+                [(-1, -1), (-1, 1), (1, -1), (1, 1)]
+                    .into_iter()
+                    .flat_map(|(sign_x, sign_y)| {
+                        let corner = centre + Position::new(sign_x * pillar_x, sign_y * pillar_y);
+                        Rect::new(corner, 2, 2).cells()
+                    })
+                    .filter(|position| bounds.contains(position))
+                    .collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use std::{fmt, path::Path};
+
+use anyhow::Context;
+
+use super::{Board, Difficulty, Direction, EdgePolicy, GameConfig, Position, SnakeGameLogic, SpeedCurve};
+
+// A level file is a header of `key: value` lines, a blank line, then the board as an ASCII grid:
+//
+//     ; lines starting with ';' are comments
+//     name: pillars
+//     speed: hard
+//     spawn: 7 4 right
+//     spawn: 7 1 left
+//     edges: walls
+//     food: 11 2
+//
+//     ...............
+//     ...#.......#...
+//     ...............
+//
+// `speed` is a difficulty preset (easy, normal, hard, insane), a starting number of ticks per second
+// that speeds up like normal, or `constant <ticks per second>`. `.` is an empty cell and `#` an
+// obstacle. Header coordinates are `column row`, counted from the top-left cell of the grid. Every
+// `spawn` adds a snake; without one a single snake starts in the centre. The board is as large as
+// the grid and centred like `Board::new`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self { line, column, message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub config: GameConfig,
+}
+
+// a header field together with where it was written, so later checks can point at it
+struct Located<T> {
+    value: T,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Default)]
+struct Header {
+    name: Option<String>,
+    speed: Option<SpeedCurve>,
+    spawns: Vec<Located<(i32, i32, Direction)>>,
+    edges: Option<EdgePolicy>,
+    food: Vec<Located<(i32, i32)>>,
+}
+
+impl Level {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read level {}", path.display()))?;
+
+        Self::parse(&source).with_context(|| format!("failed to parse level {}", path.display()))
+    }
+
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut lines = source.lines().enumerate().map(|(index, line)| (index + 1, line));
+
+        let mut header = Header::default();
+        for (line_number, line) in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                break;
+            }
+            if trimmed.starts_with(';') {
+                continue;
+            }
+
+            parse_header_line(&mut header, line_number, line)?;
+        }
+
+        let rows: Vec<(usize, &str)> = lines
+            .filter(|(_, line)| !line.trim_start().starts_with(';'))
+            .map(|(line_number, line)| (line_number, line.trim_end()))
+            .collect();
+        let last_row = rows.iter().rposition(|(_, line)| !line.is_empty());
+        let rows = &rows[..last_row.map_or(0, |index| index + 1)];
+
+        let Some(&(first_line, first_row)) = rows.first() else {
+            return Err(ParseError::new(source.lines().count() + 1, 1, "expected a grid after the header"));
+        };
+
+        let columns = first_row.chars().count();
+        if columns == 0 {
+            return Err(ParseError::new(first_line, 1, "the grid must have at least one column"));
+        }
+
+        let (width, height) = (columns as i32, rows.len() as i32);
+        let board = Board::new(width, height);
+        let to_position = |column: i32, row: i32| board.position_from_top_left(column, row);
+
+        let mut obstacles = Vec::new();
+        for (row, &(line_number, line)) in rows.iter().enumerate() {
+            let length = line.chars().count();
+            if length != columns {
+                return Err(ParseError::new(
+                    line_number,
+                    length.min(columns) + 1,
+                    format!("expected {} columns like the first row, found {}", columns, length),
+                ));
+            }
+
+            for (column, tile) in line.chars().enumerate() {
+                match tile {
+                    '.' => {}
+                    '#' => obstacles.push(to_position(column as i32, row as i32)),
+                    other => return Err(ParseError::new(line_number, column + 1, format!("unknown tile '{}', expected '.' or '#'", other))),
+                }
+            }
+        }
+
+        let check_cell = |located: &Located<(i32, i32)>, what: &str| {
+            let (column, row) = located.value;
+            if column < 0 || column >= width || row < 0 || row >= height {
+                return Err(ParseError::new(
+                    located.line,
+                    located.column,
+                    format!("{} {} {} is outside the {}x{} grid", what, column, row, width, height),
+                ));
+            }
+
+            let position = to_position(column, row);
+            if obstacles.contains(&position) {
+                return Err(ParseError::new(located.line, located.column, format!("{} {} {} is on an obstacle", what, column, row)));
+            }
+
+            Ok(position)
+        };
+
+        let defaults = GameConfig::default();
+
+        let mut spawns: Vec<(Position, Direction)> = Vec::with_capacity(header.spawns.len());
+        for located in &header.spawns {
+            let (column, row, direction) = located.value;
+            let cell = Located { value: (column, row), line: located.line, column: located.column };
+            let position = check_cell(&cell, "spawn")?;
+            if spawns.iter().any(|(other, _)| *other == position) {
+                return Err(ParseError::new(located.line, located.column, "two snakes cannot spawn on the same cell"));
+            }
+            spawns.push((position, direction));
+        }
+        if spawns.is_empty() && defaults.spawns().iter().any(|(position, _)| obstacles.contains(position)) {
+            return Err(ParseError::new(first_line, 1, "the centre of the grid is an obstacle, add a `spawn` to the header"));
+        }
+
+        let mut initial_food = Vec::with_capacity(header.food.len());
+        for located in &header.food {
+            let position = check_cell(located, "food")?;
+            let starts = if spawns.is_empty() { defaults.spawns() } else { &spawns };
+            if starts.iter().any(|(spawn, _)| *spawn == position) {
+                return Err(ParseError::new(located.line, located.column, "food cannot be placed on the spawn"));
+            }
+            if initial_food.contains(&position) {
+                return Err(ParseError::new(located.line, located.column, "two foods cannot be placed on the same cell"));
+            }
+            initial_food.push(position);
+        }
+
+        Ok(Self {
+            name: header.name.unwrap_or_else(|| "untitled".to_string()),
+            config: GameConfig {
+                board,
+                edge_policy: header.edges.unwrap_or(defaults.edge_policy),
+                obstacles,
+                speed: header.speed.unwrap_or(defaults.speed),
+                spawns,
+                initial_food,
+                food_count: defaults.food_count,
+            },
+        })
+    }
+
+    pub fn logic(&self, seed: u64) -> SnakeGameLogic {
+        SnakeGameLogic::new(self.config.clone(), seed)
+    }
+}
+
+// splits `value` into whitespace separated fields, each paired with its 1-based column in the line.
+// columns count characters, `offset` of them before `value`
+fn fields(value: &str, offset: usize) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = None;
+
+    let characters = value.char_indices().chain(std::iter::once((value.len(), ' ')));
+    for (column, (index, character)) in characters.enumerate() {
+        match (start, character.is_whitespace()) {
+            (None, false) => start = Some((column, index)),
+            (Some((begin_column, begin)), true) => {
+                fields.push((offset + begin_column + 1, &value[begin..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    fields
+}
+
+fn parse_number<T: std::str::FromStr>(line: usize, (column, field): (usize, &str), what: &str) -> Result<T, ParseError> {
+    field.parse().map_err(|_| ParseError::new(line, column, format!("expected {}, found '{}'", what, field)))
+}
+
+fn parse_ticks_per_second(line: usize, field: (usize, &str)) -> Result<f32, ParseError> {
+    let ticks_per_second: f32 = parse_number(line, field, "a number of ticks per second")?;
+    if !(ticks_per_second > 0.0 && ticks_per_second.is_finite()) {
+        return Err(ParseError::new(line, field.0, "ticks per second must be a positive number"));
+    }
+
+    Ok(ticks_per_second)
+}
+
+fn parse_speed(line: usize, key_column: usize, fields: &[(usize, &str)]) -> Result<SpeedCurve, ParseError> {
+    match fields {
+        [(_, "constant"), ticks_per_second] => Ok(SpeedCurve::Constant {
+            ticks_per_second: parse_ticks_per_second(line, *ticks_per_second)?,
+        }),
+        [(column, name)] if name.starts_with(|c: char| c.is_ascii_alphabetic()) => Difficulty::from_name(name)
+            .map(|difficulty| difficulty.speed_curve())
+            .ok_or_else(|| ParseError::new(line, *column, format!("unknown difficulty '{}', expected easy, normal, hard or insane", name))),
+        [base] => Ok(SpeedCurve::growing_from(parse_ticks_per_second(line, *base)?)),
+        _ => {
+            let column = fields.first().map_or(key_column, |(column, _)| *column);
+            Err(ParseError::new(line, column, "expected `<difficulty>`, `<ticks per second>` or `constant <ticks per second>`"))
+        }
+    }
+}
+
+fn parse_direction(line: usize, (column, field): (usize, &str)) -> Result<Direction, ParseError> {
+    match field {
+        "up" => Ok(Direction::Up),
+        "down" => Ok(Direction::Down),
+        "left" => Ok(Direction::Left),
+        "right" => Ok(Direction::Right),
+        other => Err(ParseError::new(line, column, format!("unknown direction '{}', expected up, down, left or right", other))),
+    }
+}
+
+fn expect_fields(line: usize, key_column: usize, fields: &[(usize, &str)], usage: &str) -> Result<(), ParseError> {
+    let expected = usage.split_whitespace().count();
+    if fields.len() == expected {
+        return Ok(());
+    }
+
+    let column = fields.get(expected).map_or(key_column, |(column, _)| *column);
+    Err(ParseError::new(line, column, format!("expected `{}`", usage)))
+}
+
+fn parse_header_line(header: &mut Header, line_number: usize, line: &str) -> Result<(), ParseError> {
+    let key_column = line.chars().count() - line.trim_start().chars().count() + 1;
+    let Some((key, value)) = line.split_once(':') else {
+        return Err(ParseError::new(line_number, key_column, "expected `key: value` in the header"));
+    };
+
+    let fields = fields(value, key.chars().count() + 1);
+    let duplicate = || ParseError::new(line_number, key_column, format!("`{}` is given more than once", key.trim()));
+
+    match key.trim() {
+        "name" => {
+            if header.name.is_some() {
+                return Err(duplicate());
+            }
+            header.name = Some(value.trim().to_string());
+        }
+        "speed" => {
+            if header.speed.is_some() {
+                return Err(duplicate());
+            }
+
+            header.speed = Some(parse_speed(line_number, key_column, &fields)?);
+        }
+        "spawn" => {
+            expect_fields(line_number, key_column, &fields, "column row direction")?;
+
+            header.spawns.push(Located {
+                value: (
+                    parse_number(line_number, fields[0], "a column")?,
+                    parse_number(line_number, fields[1], "a row")?,
+                    parse_direction(line_number, fields[2])?,
+                ),
+                line: line_number,
+                column: fields[0].0,
+            });
+        }
+        "edges" => {
+            expect_fields(line_number, key_column, &fields, "walls|wrap")?;
+            if header.edges.is_some() {
+                return Err(duplicate());
+            }
+
+            header.edges = Some(match fields[0].1 {
+                "walls" => EdgePolicy::Walls,
+                "wrap" => EdgePolicy::Wrap,
+                other => return Err(ParseError::new(line_number, fields[0].0, format!("unknown edge policy '{}', expected walls or wrap", other))),
+            });
+        }
+        "food" => {
+            expect_fields(line_number, key_column, &fields, "column row")?;
+
+            header.food.push(Located {
+                value: (
+                    parse_number(line_number, fields[0], "a column")?,
+                    parse_number(line_number, fields[1], "a row")?,
+                ),
+                line: line_number,
+                column: fields[0].0,
+            });
+        }
+        other => {
+            return Err(ParseError::new(
+                line_number,
+                key_column,
+                format!("unknown header key '{}', expected name, speed, spawn, edges or food", other),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(source: &str) -> (usize, usize) {
+        let error = Level::parse(source).expect_err("the level should not parse");
+        (error.line, error.column)
+    }
+
+    #[test]
+    fn parses_a_valid_level() {
+        let level = Level::parse("name: test\nspeed: constant 4\nspawn: 0 0 right\nfood: 4 2\n\n.....\n..#..\n.....\n").unwrap();
+
+        assert_eq!(level.name, "test");
+        assert_eq!(level.config.speed, SpeedCurve::Constant { ticks_per_second: 4.0 });
+        assert_eq!(level.config.obstacles.len(), 1);
+        assert_eq!(level.config.spawns.len(), 1);
+        assert_eq!(level.config.initial_food.len(), 1);
+    }
+
+    #[test]
+    fn points_at_ragged_rows() {
+        assert_eq!(error_at("name: x\n\n...\n..\n"), (4, 3));
+        assert_eq!(error_at("name: x\n\n...\n....\n"), (4, 4));
+    }
+
+    #[test]
+    fn points_at_unknown_tiles() {
+        assert_eq!(error_at("name: x\n\n...\n.x.\n"), (4, 2));
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        assert_eq!(error_at("name: x\n\n...\n.é.\n"), (4, 2));
+        assert_eq!(error_at("name: x\n\n.é.\n...\n"), (3, 2));
+        assert_eq!(error_at("spawn:é 9 0 up\n\n...\n"), (1, 13));
+        assert_eq!(error_at("  é: 1\n\n...\n"), (1, 3));
+        assert_eq!(error_at("name: x\nspeed: é fast\n\n...\n"), (2, 8));
+    }
+
+    #[test]
+    fn points_at_spawns_outside_the_grid_or_on_obstacles() {
+        assert_eq!(error_at("spawn: 5 0 right\n\n...\n...\n"), (1, 8));
+        assert_eq!(error_at("name: x\nspawn: 0 -1 up\n\n...\n...\n"), (2, 8));
+        assert_eq!(error_at("spawn: 1 0 right\n\n.#.\n...\n"), (1, 8));
+    }
+
+    #[test]
+    fn points_at_duplicates() {
+        assert_eq!(error_at("speed: hard\nspeed: easy\n\n...\n"), (2, 1));
+        assert_eq!(error_at("spawn: 0 0 right\nspawn: 0 0 up\n\n...\n"), (2, 8));
+        assert_eq!(error_at("food: 0 0\nfood: 0 0\n\n.....\n.....\n.....\n"), (2, 7));
+    }
+
+    #[test]
+    fn points_at_bad_speeds() {
+        assert_eq!(error_at("speed: fast\n\n...\n"), (1, 8));
+        assert_eq!(error_at("speed: -2\n\n...\n"), (1, 8));
+        assert_eq!(error_at("speed: constant 0\n\n...\n"), (1, 17));
+        assert_eq!(error_at("speed: constant 4 5\n\n...\n"), (1, 8));
+    }
+}
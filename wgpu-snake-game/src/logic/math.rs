@@ -0,0 +1,243 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+// a cell on the board, or the offset between two cells; `y` grows upwards like on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+impl Position {
+    pub const ZERO: Position = Position { x: 0, y: 0 };
+
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+
+    pub fn manhattan_distance(&self, other: &Position) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn step(&self, direction: Direction) -> Position {
+        *self + direction.convert_to_position()
+    }
+
+    // the four orthogonally adjacent cells, in `Direction::ALL` order
+    pub fn neighbours(&self) -> impl Iterator<Item = Position> {
+        let origin = *self;
+        Direction::ALL.into_iter().map(move |direction| origin.step(direction))
+    }
+
+    pub fn wrap(&self, bounds: &Rect) -> Position {
+        bounds.wrap(*self)
+    }
+}
+
+impl From<(i32, i32)> for Position {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl Add for Position {
+    type Output = Position;
+
+    fn add(self, other: Position) -> Position {
+        Position::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Position {
+    type Output = Position;
+
+    fn sub(self, other: Position) -> Position {
+        Position::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Position {
+    type Output = Position;
+
+    fn mul(self, factor: i32) -> Position {
+        Position::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Position {
+    type Output = Position;
+
+    fn neg(self) -> Position {
+        Position::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Position {
+    fn add_assign(&mut self, other: Position) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Position {
+    fn sub_assign(&mut self, other: Position) {
+        *self = *self - other;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    pub fn convert_to_position(&self) -> Position {
+        match self {
+            Direction::Up => Position::new(0, 1),
+            Direction::Down => Position::new(0, -1),
+            Direction::Left => Position::new(-1, 0),
+            Direction::Right => Position::new(1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    // a quarter turn anticlockwise, as seen from above with y pointing up
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        self.turn_left().opposite()
+    }
+
+    // the direction of a unit step from `from` to `to`, if they are orthogonal neighbours
+    pub fn between(from: &Position, to: &Position) -> Option<Direction> {
+        Direction::ALL.into_iter().find(|direction| from.step(*direction) == *to)
+    }
+}
+
+// an axis aligned block of `width` x `height` cells starting at `min`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Rect {
+    min: Position,
+    width: i32,
+    height: i32,
+}
+
+impl Rect {
+    pub fn new(min: Position, width: i32, height: i32) -> Self {
+        Self { min, width: width.max(0), height: height.max(0) }
+    }
+
+    // both corners are inclusive
+    pub fn from_corners(min: Position, max: Position) -> Self {
+        Self::new(min, max.x - min.x + 1, max.y - min.y + 1)
+    }
+
+    pub fn min(&self) -> Position {
+        self.min
+    }
+
+    // the inclusive upper corner
+    pub fn max(&self) -> Position {
+        self.min + Position::new(self.width - 1, self.height - 1)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn area(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        let offset = *position - self.min;
+        (0..self.width).contains(&offset.x) && (0..self.height).contains(&offset.y)
+    }
+
+    // maps a position outside the rect back in from the opposite edge
+    pub fn wrap(&self, position: Position) -> Position {
+        let offset = position - self.min;
+        self.min + Position::new(offset.x.rem_euclid(self.width.max(1)), offset.y.rem_euclid(self.height.max(1)))
+    }
+
+    // row-major index of a contained position, starting from `min`
+    pub fn index_of(&self, position: &Position) -> Option<usize> {
+        if !self.contains(position) {
+            return None;
+        }
+
+        let offset = *position - self.min;
+        Some((offset.y * self.width + offset.x) as usize)
+    }
+
+    // the inverse of `index_of`; `None` past the last cell
+    pub fn position_at(&self, index: usize) -> Option<Position> {
+        if index >= self.area() {
+            return None;
+        }
+
+        let index = index as i32;
+        Some(self.min + Position::new(index % self.width, index / self.width))
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Position> {
+        let rect = *self;
+        (0..rect.area()).filter_map(move |index| rect.position_at(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_at_is_the_inverse_of_index_of() {
+        let rect = Rect::new(Position::new(-2, 3), 4, 3);
+        for (index, position) in rect.cells().enumerate() {
+            assert_eq!(rect.index_of(&position), Some(index));
+            assert_eq!(rect.position_at(index), Some(position));
+        }
+        assert_eq!(rect.cells().count(), rect.area());
+        assert_eq!(rect.position_at(rect.area()), None);
+    }
+
+    #[test]
+    fn empty_rects_have_no_positions() {
+        for rect in [Rect::new(Position::ZERO, 0, 3), Rect::new(Position::ZERO, 3, 0)] {
+            assert_eq!(rect.position_at(0), None);
+            assert_eq!(rect.cells().count(), 0);
+        }
+    }
+}
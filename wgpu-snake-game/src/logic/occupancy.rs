@@ -0,0 +1,141 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{math::Position, Board, FoodKind};
+
+const NOT_FREE: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cell {
+    Empty,
+    Obstacle,
+    Snake,
+    Food(FoodKind),
+}
+
+// what sits on every board cell, plus a packed list of the empty ones so a random free cell can be
+// drawn in constant time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OccupancyGrid {
+    board: Board,
+    cells: Vec<Cell>,
+    free: Vec<u32>,
+    free_slots: Vec<u32>,
+}
+
+impl OccupancyGrid {
+    pub fn new(board: Board) -> Self {
+        let count = board.cell_count();
+
+        Self {
+            board,
+            cells: vec![Cell::Empty; count],
+            free: (0..count as u32).collect(),
+            free_slots: (0..count as u32).collect(),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    // `None` when the position is off the board
+    pub fn get(&self, position: &Position) -> Option<Cell> {
+        self.board.index_of(position).map(|index| self.cells[index])
+    }
+
+    pub fn is_free(&self, position: &Position) -> bool {
+        self.get(position) == Some(Cell::Empty)
+    }
+
+    // stores `cell` at `position` and returns what was there, ignoring positions off the board
+    pub fn set(&mut self, position: &Position, cell: Cell) -> Option<Cell> {
+        let index = self.board.index_of(position)?;
+        let previous = std::mem::replace(&mut self.cells[index], cell);
+
+        match (previous == Cell::Empty, cell == Cell::Empty) {
+            (true, false) => {
+                let slot = std::mem::replace(&mut self.free_slots[index], NOT_FREE) as usize;
+                self.free.swap_remove(slot);
+                if let Some(&moved) = self.free.get(slot) {
+                    self.free_slots[moved as usize] = slot as u32;
+                }
+            }
+            (false, true) => {
+                self.free_slots[index] = self.free.len() as u32;
+                self.free.push(index as u32);
+            }
+            _ => {}
+        }
+
+        Some(previous)
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn random_free(&self, rng: &mut impl Rng) -> Option<Position> {
+        if self.free.is_empty() {
+            return None;
+        }
+
+        let index = self.free[rng.gen_range(0..self.free.len())];
+        self.board.position_at(index as usize)
+    }
+
+    // whether the cells and the free list agree with each other and with the board, as `set` keeps
+    // them; only a grid that was not built through `set`, such as a loaded one, can fail this
+    pub(super) fn is_consistent(&self) -> bool {
+        let count = self.board.cell_count();
+        if self.cells.len() != count || self.free_slots.len() != count {
+            return false;
+        }
+
+        let empty = self.cells.iter().filter(|cell| **cell == Cell::Empty).count();
+        let listed = self.free.iter().enumerate().all(|(slot, &index)| {
+            let index = index as usize;
+            index < count && self.cells[index] == Cell::Empty && self.free_slots[index] == slot as u32
+        });
+        let unlisted = (0..count).all(|index| self.cells[index] == Cell::Empty || self.free_slots[index] == NOT_FREE);
+
+        self.free.len() == empty && listed && unlisted
+    }
+
+    pub fn free_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.free.iter().filter_map(|&index| self.board.position_at(index as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::logic::GameRng;
+
+    #[test]
+    fn free_cells_follow_random_sets() {
+        let board = Board::new(7, 5);
+        let mut grid = OccupancyGrid::new(board);
+        let mut rng = GameRng::seed_from_u64(3);
+        let kinds = [Cell::Empty, Cell::Obstacle, Cell::Snake, Cell::Food(FoodKind::Regular)];
+
+        for _ in 0..2000 {
+            let position = board.origin() + Position::new(rng.gen_range(-1..8), rng.gen_range(-1..6));
+            let cell = kinds[rng.gen_range(0..kinds.len())];
+            grid.set(&position, cell);
+
+            let expected: HashSet<Position> = (0..grid.cells.len())
+                .filter(|index| grid.cells[*index] == Cell::Empty)
+                .filter_map(|index| board.position_at(index))
+                .collect();
+            let free: Vec<Position> = grid.free_cells().collect();
+            assert_eq!(grid.free_count(), expected.len());
+            assert_eq!(free.len(), expected.len());
+            assert_eq!(free.into_iter().collect::<HashSet<_>>(), expected);
+        }
+    }
+}
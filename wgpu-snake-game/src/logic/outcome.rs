@@ -0,0 +1,46 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathCause {
+    Wall,
+    SelfCollision,
+    OtherSnake,
+    Obstacle,
+    Poison,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOutcome {
+    Died(DeathCause),
+    Won,
+    // with several snakes: the one left when every other snake is out, or the best scorer when the
+    // board fills up
+    PlayerWon(usize),
+    // with several snakes: the last ones went out on the same tick, or tied for the best score
+    Draw,
+}
+
+impl fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeathCause::Wall => write!(f, "hit the wall"),
+            DeathCause::SelfCollision => write!(f, "ran into itself"),
+            DeathCause::OtherSnake => write!(f, "ran into another snake"),
+            DeathCause::Obstacle => write!(f, "hit an obstacle"),
+            DeathCause::Poison => write!(f, "ate poison"),
+        }
+    }
+}
+
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameOutcome::Died(cause) => write!(f, "game over: the snake {}", cause),
+            GameOutcome::Won => write!(f, "you win: the board is full"),
+            GameOutcome::PlayerWon(snake) => write!(f, "game over: player {} wins", snake + 1),
+            GameOutcome::Draw => write!(f, "game over: a draw"),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use super::GameOutcome;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamePhase {
+    Title,
+    Playing,
+    Paused,
+    GameOver(GameOutcome),
+}
+
+impl GamePhase {
+    pub fn is_playing(&self) -> bool {
+        matches!(self, GamePhase::Playing)
+    }
+
+    pub fn start(self) -> Self {
+        match self {
+            GamePhase::Title => GamePhase::Playing,
+            other => other,
+        }
+    }
+
+    pub fn toggle_pause(self) -> Self {
+        match self {
+            GamePhase::Playing => GamePhase::Paused,
+            GamePhase::Paused => GamePhase::Playing,
+            other => other,
+        }
+    }
+
+    pub fn finish(self, outcome: GameOutcome) -> Self {
+        match self {
+            GamePhase::Playing => GamePhase::GameOver(outcome),
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::DeathCause;
+
+    #[test]
+    fn a_round_goes_from_the_title_through_pauses_to_game_over() {
+        let outcome = GameOutcome::Died(DeathCause::Wall);
+
+        let phase = GamePhase::Title;
+        assert_eq!(phase.toggle_pause(), GamePhase::Title);
+        assert_eq!(phase.finish(outcome), GamePhase::Title);
+
+        let phase = phase.start();
+        assert!(phase.is_playing());
+        let paused = phase.toggle_pause();
+        assert_eq!(paused, GamePhase::Paused);
+        assert_eq!(paused.finish(outcome), GamePhase::Paused);
+        assert_eq!(paused.toggle_pause(), GamePhase::Playing);
+
+        let over = phase.finish(outcome);
+        assert_eq!(over, GamePhase::GameOver(outcome));
+        assert_eq!(over.start(), over);
+        assert_eq!(over.toggle_pause(), over);
+    }
+}
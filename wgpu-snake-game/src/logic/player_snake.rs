@@ -1,67 +1,101 @@
 use std::collections::VecDeque;
 
-use super::math::Position;
+use serde::{Deserialize, Serialize};
 
+use super::{math::{Direction, Position}, DeathCause, Score};
+
+const MAX_QUEUED_DIRECTIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayerSnake {
     head: Position,
     body: VecDeque<Position>,
     direction: Direction,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    pub fn convert_to_position(&self) -> Position {
-        match self {
-            Direction::Up => Position::new(0, -1),
-            Direction::Down => Position::new(0, 1),
-            Direction::Left => Position::new(-1, 0),
-            Direction::Right => Position::new(1, 0),
-        }
-    }
+    queued_directions: VecDeque<Direction>,
+    pending_growth: usize,
+    score: Score,
+    // why the snake is out of the game, `None` while it is still playing
+    death: Option<DeathCause>,
 }
 
 impl Default for PlayerSnake {
     fn default() -> Self {
-        Self::new()
+        Self::new(Position::ZERO, Direction::Right)
     }
 }
 
 impl PlayerSnake {
-    pub fn new() -> Self {
-        let head = Position::new(0, 0);
+    pub fn new(head: Position, direction: Direction) -> Self {
         let body = VecDeque::new();
 
         Self {
             head,
             body,
-            direction: Direction::Right,
+            direction,
+            queued_directions: VecDeque::with_capacity(MAX_QUEUED_DIRECTIONS),
+            pending_growth: 0,
+            score: Score::new(),
+            death: None,
         }
     }
 
-    pub fn update(&mut self) {
-        let new_head = Position::new(
-            self.head.x() + self.direction.convert_to_position().x(),
-            self.head.y() + self.direction.convert_to_position().y(),
-        );
+    // moves one cell forward and returns the cell the tail left, if it moved
+    pub fn update(&mut self, edge: impl Fn(Position) -> Position) -> Option<Position> {
+        if let Some(direction) = self.queued_directions.pop_front() {
+            self.direction = direction;
+        }
+
+        let new_head = edge(self.head.step(self.direction));
 
         self.body.push_front(self.head);
         self.head = new_head;
-        self.body.pop_back();
+
+        // the tail stays put while growing
+        if self.pending_growth > 0 {
+            self.pending_growth -= 1;
+            None
+        } else {
+            self.body.pop_back()
+        }
+    }
+
+    pub fn grow(&mut self, segments: usize) {
+        self.pending_growth += segments;
+    }
+
+    // drops up to `segments` from the tail, never the head, and returns the cells that were freed
+    pub fn shrink(&mut self, segments: usize) -> Vec<Position> {
+        let from_pending = segments.min(self.pending_growth);
+        self.pending_growth -= from_pending;
+
+        let from_body = (segments - from_pending).min(self.body.len());
+        self.body.drain(self.body.len() - from_body..).collect()
+    }
+
+    // queues a turn for a later tick; each tick consumes one entry, so turns are checked
+    // against the direction the snake will actually be travelling when they are applied
+    pub fn queue_direction(&mut self, direction: Direction) -> bool {
+        let last = self.heading();
+        if direction == last || direction == last.opposite() || self.queued_directions.len() >= MAX_QUEUED_DIRECTIONS {
+            return false;
+        }
+
+        self.queued_directions.push_back(direction);
+        true
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
     }
 
-    pub fn grow(&mut self) {
-        self.body.push_back(self.head);
+    // the direction of the next move: the last queued turn, or the current direction
+    pub fn heading(&self) -> Direction {
+        self.queued_directions.back().copied().unwrap_or(self.direction)
     }
 
-    pub fn set_direction(&mut self, direction: Direction) {
-        self.direction = direction;
+    // how many more ticks the tail stays put
+    pub fn pending_growth(&self) -> usize {
+        self.pending_growth
     }
 
     pub fn head(&self) -> &Position {
@@ -71,4 +105,58 @@ impl PlayerSnake {
     pub fn body(&self) -> &VecDeque<Position> {
         &self.body
     }
-}
\ No newline at end of file
+
+    pub fn length(&self) -> usize {
+        self.body.len() + 1
+    }
+
+    pub fn occupies(&self, position: &Position) -> bool {
+        &self.head == position || self.body.contains(position)
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    pub(super) fn score_mut(&mut self) -> &mut Score {
+        &mut self.score
+    }
+
+    pub fn death(&self) -> Option<DeathCause> {
+        self.death
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.death.is_none()
+    }
+
+    pub(super) fn kill(&mut self, cause: DeathCause) {
+        self.death = Some(cause);
+        self.queued_directions.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_turns_apply_one_per_tick_and_never_reverse() {
+        let mut snake = PlayerSnake::new(Position::new(0, 0), Direction::Right);
+        assert!(!snake.queue_direction(Direction::Left));
+        assert!(!snake.queue_direction(Direction::Right));
+
+        assert!(snake.queue_direction(Direction::Up));
+        assert!(snake.queue_direction(Direction::Left));
+        assert!(!snake.queue_direction(Direction::Right));
+        assert!(snake.queue_direction(Direction::Down));
+        assert!(!snake.queue_direction(Direction::Right));
+
+        let mut directions = Vec::new();
+        for _ in 0..4 {
+            snake.update(|position| position);
+            directions.push(snake.direction());
+        }
+        assert_eq!(directions, [Direction::Up, Direction::Left, Direction::Down, Direction::Down]);
+    }
+}
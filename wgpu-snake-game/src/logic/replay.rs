@@ -0,0 +1,194 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    save::{check_version, is_json, SaveHeader},
+    timer::LogicTimer,
+    GameConfig, GameEvent, GameOutcome, Input, SnakeGameLogic,
+};
+
+// bump whenever a change to the rules would make older replays play out differently
+pub const REPLAY_VERSION: u32 = 2;
+
+// everything needed to re-simulate a game: the seed, the rules and the input of every tick. the
+// result at the end is kept too, so playback can tell when the simulation no longer matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    pub seed: u64,
+    pub config: GameConfig,
+    // each input with the tick it was applied right before, in order
+    inputs: Vec<(u64, Input)>,
+    ticks: u64,
+    outcome: Option<GameOutcome>,
+    // for every snake
+    points: Vec<u64>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, config: GameConfig) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            config,
+            inputs: Vec::new(),
+            ticks: 0,
+            outcome: None,
+            points: Vec::new(),
+        }
+    }
+
+    // a fresh game to play the replay on
+    pub fn start(&self) -> SnakeGameLogic {
+        SnakeGameLogic::new(self.config.clone(), self.seed)
+    }
+
+    // `inputs` were applied to the game while its next tick was `tick`
+    pub fn record(&mut self, tick: u64, inputs: &[Input]) {
+        debug_assert!(self.inputs.last().is_none_or(|(last, _)| *last <= tick));
+        self.inputs.extend(inputs.iter().map(|input| (tick, *input)));
+    }
+
+    // stores how far the recorded game got and how it ended
+    pub fn finish(&mut self, logic: &SnakeGameLogic) {
+        self.ticks = logic.tick();
+        self.outcome = logic.outcome();
+        self.points = points(logic);
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.outcome
+    }
+
+    pub fn points(&self) -> &[u64] {
+        &self.points
+    }
+
+    pub fn inputs(&self) -> &[(u64, Input)] {
+        &self.inputs
+    }
+
+    // plays the whole replay without a clock and returns the final state
+    pub fn simulate(&self) -> SnakeGameLogic {
+        let mut logic = self.start();
+        let mut player = ReplayPlayer::new(self.clone());
+        while !player.is_finished(&logic) {
+            player.step(&mut logic);
+        }
+
+        logic
+    }
+
+    // `None` when `logic` ended the way the recorded game did, otherwise what differs
+    pub fn mismatch(&self, logic: &SnakeGameLogic) -> Option<String> {
+        let expected = (self.ticks, self.outcome, self.points.clone());
+        let found = (logic.tick(), logic.outcome(), points(logic));
+        (expected != found).then(|| format!(
+            "expected tick {} with {:?} and points {:?}, found tick {} with {:?} and points {:?}",
+            expected.0, expected.1, expected.2, found.0, found.1, found.2,
+        ))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let contents = if is_json(path) {
+            serde_json::to_vec_pretty(self).context("failed to serialize the replay")?
+        } else {
+            bincode::serialize(self).context("failed to serialize the replay")?
+        };
+
+        std::fs::write(path, contents).with_context(|| format!("failed to write replay {}", path.display()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path).with_context(|| format!("failed to read replay {}", path.display()))?;
+
+        Self::parse(&contents, is_json(path)).with_context(|| format!("failed to load replay {}", path.display()))
+    }
+
+    fn parse(contents: &[u8], json: bool) -> anyhow::Result<Self> {
+        // the version is the first field in both formats, so it can be checked before the rest
+        let replay: Self = if json {
+            let header: SaveHeader = serde_json::from_slice(contents).context("failed to read the replay header")?;
+            check_version("replay", header.version, REPLAY_VERSION)?;
+            serde_json::from_slice(contents).context("failed to deserialize the replay")?
+        } else {
+            let version: u32 = bincode::deserialize(contents).context("failed to read the replay header")?;
+            check_version("replay", version, REPLAY_VERSION)?;
+            bincode::deserialize(contents).context("failed to deserialize the replay")?
+        };
+
+        if replay.inputs.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+            bail!("the replay inputs are out of order");
+        }
+
+        Ok(replay)
+    }
+}
+
+fn points(logic: &SnakeGameLogic) -> Vec<u64> {
+    logic.snakes().iter().map(|snake| snake.score().points()).collect()
+}
+
+// feeds a replay's inputs into a game tick by tick, either on its own clock or as fast as asked
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    timer: LogicTimer,
+    next_input: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            timer: LogicTimer::new(),
+            next_input: 0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // back to the first tick, returning a fresh game to play on
+    pub fn rewind(&mut self) -> SnakeGameLogic {
+        self.timer = LogicTimer::new();
+        self.next_input = 0;
+        self.replay.start()
+    }
+
+    pub fn is_finished(&self, logic: &SnakeGameLogic) -> bool {
+        logic.is_over() || logic.tick() >= self.replay.ticks
+    }
+
+    // runs the next recorded tick on `logic`
+    pub fn step(&mut self, logic: &mut SnakeGameLogic) -> Vec<GameEvent> {
+        let tick = logic.tick() + 1;
+        let start = self.next_input;
+        while self.replay.inputs.get(self.next_input).is_some_and(|(input_tick, _)| *input_tick <= tick) {
+            self.next_input += 1;
+        }
+
+        let inputs: Vec<Input> = self.replay.inputs[start..self.next_input].iter().map(|(_, input)| *input).collect();
+        logic.step(&inputs)
+    }
+
+    // plays back in real time at the game's own speed, like `SnakeGameLogic::advance`
+    pub fn advance(&mut self, logic: &mut SnakeGameLogic, elapsed: Duration) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.timer.accumulate(elapsed, logic.ticks_per_second());
+        while !self.is_finished(logic) && self.timer.try_tick(logic.ticks_per_second()) {
+            events.extend(self.step(logic));
+        }
+
+        events
+    }
+}
@@ -0,0 +1,255 @@
+use std::{io::Read, path::Path};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use super::{occupancy::{Cell, OccupancyGrid}, SnakeGameLogic};
+
+// bump whenever a change to the logic state would make older saves load into a different game
+pub const SAVE_VERSION: u32 = 2;
+
+// the whole simulation, rng included, so a loaded game continues exactly like the saved one would have
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u32,
+    game: &'a SnakeGameLogic,
+}
+
+// the version was already checked through `SaveHeader`
+#[derive(Deserialize)]
+struct LoadedSave {
+    game: SnakeGameLogic,
+}
+
+// only the version, read first so an old or newer save gets a clear error instead of a field mismatch
+#[derive(Deserialize)]
+pub(super) struct SaveHeader {
+    pub(super) version: u32,
+}
+
+pub(super) fn check_version(what: &str, version: u32, expected: u32) -> anyhow::Result<()> {
+    if version != expected {
+        bail!("unsupported {} version {}, expected {}", what, version, expected);
+    }
+
+    Ok(())
+}
+
+// `.json` files are text, anything else is bincode
+pub(super) fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "json")
+}
+
+impl SnakeGameLogic {
+    // a save holds the grid alongside the snakes and food it is derived from, so a truncated or
+    // edited one can disagree with itself; that is an error here rather than a panic a few ticks later
+    fn check_loaded(&self) -> anyhow::Result<()> {
+        if !self.config.speed.is_finite() || self.speed_effect.is_some_and(|(factor, _)| !(factor.is_finite() && factor > 0.0)) {
+            bail!("the save has an invalid speed");
+        }
+        if self.grid.board() != &self.config.board || !self.grid.is_consistent() {
+            bail!("the save's occupancy grid does not fit its board");
+        }
+
+        let mut expected = OccupancyGrid::new(self.config.board);
+        for obstacle in &self.config.obstacles {
+            expected.set(obstacle, Cell::Obstacle);
+        }
+        for snake in self.snakes.iter().filter(|snake| snake.is_alive()) {
+            for segment in std::iter::once(snake.head()).chain(snake.body()) {
+                if expected.set(segment, Cell::Snake) != Some(Cell::Empty) {
+                    bail!("the save has a snake off the board or on top of something");
+                }
+            }
+        }
+        for item in self.food.items() {
+            if expected.set(&item.position, Cell::Food(item.kind)) != Some(Cell::Empty) {
+                bail!("the save has food off the board or on top of something");
+            }
+        }
+
+        if let Some(position) = self.config.board.cells().find(|position| expected.get(position) != self.grid.get(position)) {
+            bail!("the save's occupancy grid disagrees with the game at {} {}", position.x(), position.y());
+        }
+
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(&SaveFile { version: SAVE_VERSION, game: self }).context("failed to serialize the game")
+    }
+
+    pub fn from_json(source: &str) -> anyhow::Result<Self> {
+        let header: SaveHeader = serde_json::from_str(source).context("failed to read the save header")?;
+        check_version("save", header.version, SAVE_VERSION)?;
+
+        let save: LoadedSave = serde_json::from_str(source).context("failed to deserialize the game")?;
+        save.game.check_loaded()?;
+        Ok(save.game)
+    }
+
+    // the same data as `to_json` in bincode, the version first as a little endian u32
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(&SaveFile { version: SAVE_VERSION, game: self }).context("failed to serialize the game")
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> anyhow::Result<Self> {
+        let version: u32 = bincode::deserialize_from(bytes.by_ref()).context("failed to read the save header")?;
+        check_version("save", version, SAVE_VERSION)?;
+
+        let game: Self = bincode::deserialize_from(bytes.by_ref()).context("failed to deserialize the game")?;
+        if !bytes.is_empty() {
+            bail!("{} unexpected trailing bytes after the game", bytes.len());
+        }
+
+        game.check_loaded()?;
+        Ok(game)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let contents = if is_json(path) { self.to_json()?.into_bytes() } else { self.to_bytes()? };
+
+        std::fs::write(path, contents).with_context(|| format!("failed to write save {}", path.display()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path).with_context(|| format!("failed to read save {}", path.display()))?;
+
+        let game = if is_json(path) {
+            let source = std::str::from_utf8(&contents).context("the save is not valid utf-8")?;
+            Self::from_json(source)
+        } else {
+            Self::from_bytes(&contents)
+        };
+
+        game.with_context(|| format!("failed to load save {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{controller, EdgePolicy, FoodCount, GameConfig, Input};
+
+    // steers snake 0 with a seeded bot, the same way whichever copy of a game it plays
+    fn play(logic: &mut SnakeGameLogic, ticks: usize) {
+        let mut bot = controller::by_name("greedy", 7).unwrap();
+        for _ in 0..ticks {
+            let inputs: Vec<Input> = bot
+                .decide(logic, 0)
+                .map(|direction| Input::Turn { snake: 0, direction })
+                .into_iter()
+                .collect();
+            logic.step(&inputs);
+        }
+    }
+
+    fn played_game(ticks: usize) -> SnakeGameLogic {
+        let config = GameConfig { edge_policy: EdgePolicy::Wrap, food_count: FoodCount::Fixed(3), ..GameConfig::default() };
+        let mut logic = SnakeGameLogic::new(config, 42);
+        play(&mut logic, ticks);
+        logic
+    }
+
+    #[test]
+    fn a_loaded_game_continues_like_the_saved_one() {
+        let mut original = played_game(200);
+        assert!(!original.is_over());
+        let mut from_json = SnakeGameLogic::from_json(&original.to_json().unwrap()).unwrap();
+        let mut from_bytes = SnakeGameLogic::from_bytes(&original.to_bytes().unwrap()).unwrap();
+        assert_eq!(from_json, original);
+        assert_eq!(from_bytes, original);
+
+        play(&mut original, 300);
+        play(&mut from_json, 300);
+        play(&mut from_bytes, 300);
+        assert_eq!(from_json, original);
+        assert_eq!(from_bytes, original);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let logic = played_game(10);
+
+        let json = logic.to_json().unwrap().replacen(&format!("\"version\": {}", SAVE_VERSION), &format!("\"version\": {}", SAVE_VERSION + 1), 1);
+        let error = SnakeGameLogic::from_json(&json).unwrap_err();
+        assert_eq!(error.to_string(), format!("unsupported save version {}, expected {}", SAVE_VERSION + 1, SAVE_VERSION));
+
+        let mut bytes = logic.to_bytes().unwrap();
+        bytes[..4].copy_from_slice(&(SAVE_VERSION + 1).to_le_bytes());
+        let error = SnakeGameLogic::from_bytes(&bytes).unwrap_err();
+        assert_eq!(error.to_string(), format!("unsupported save version {}, expected {}", SAVE_VERSION + 1, SAVE_VERSION));
+    }
+
+    #[test]
+    fn every_state_of_a_game_passes_the_load_checks() {
+        for seed in 0..20 {
+            let mut config = GameConfig {
+                board: crate::logic::Board::new(12, 9),
+                edge_policy: if seed % 2 == 0 { EdgePolicy::Walls } else { EdgePolicy::Wrap },
+                food_count: FoodCount::Fixed(6),
+                ..GameConfig::default()
+            };
+            config.set_players(3);
+            let mut logic = SnakeGameLogic::new(config, seed);
+            let mut bots: Vec<_> = (0..3).map(|snake| controller::by_name("greedy", seed + snake).unwrap()).collect();
+
+            while !logic.is_over() && logic.tick() < 2000 {
+                let inputs: Vec<Input> = (0..3)
+                    .filter_map(|snake| bots[snake].decide(&logic, snake).map(|direction| Input::Turn { snake, direction }))
+                    .collect();
+                logic.step(&inputs);
+                logic.check_loaded().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_a_grid_that_disagrees_with_the_game() {
+        let logic = played_game(50);
+        let save: serde_json::Value = serde_json::from_str(&logic.to_json().unwrap()).unwrap();
+        let rejects = |edit: &dyn Fn(&mut serde_json::Value)| {
+            let mut save = save.clone();
+            edit(&mut save);
+            SnakeGameLogic::from_json(&save.to_string()).unwrap_err().to_string()
+        };
+
+        assert_eq!(
+            rejects(&|save| {
+                save.pointer_mut("/game/grid/cells").unwrap().as_array_mut().unwrap().pop();
+            }),
+            "the save's occupancy grid does not fit its board",
+        );
+        assert_eq!(
+            rejects(&|save| {
+                save.pointer_mut("/game/grid/free").unwrap().as_array_mut().unwrap().pop();
+            }),
+            "the save's occupancy grid does not fit its board",
+        );
+
+        // a free cell turned into an obstacle, with the free list still agreeing
+        let free = logic.grid().free_cells().next().unwrap();
+        let edited = rejects(&|save| {
+            let mut grid = logic.grid().clone();
+            grid.set(&free, Cell::Obstacle);
+            *save.pointer_mut("/game/grid").unwrap() = serde_json::to_value(&grid).unwrap();
+        });
+        assert_eq!(edited, format!("the save's occupancy grid disagrees with the game at {} {}", free.x(), free.y()));
+
+        assert_eq!(
+            rejects(&|save| *save.pointer_mut("/game/snakes/0/head").unwrap() = serde_json::json!({ "x": 1000, "y": 0 })),
+            "the save has a snake off the board or on top of something",
+        );
+    }
+
+    #[test]
+    fn rejects_a_speed_that_cannot_tick() {
+        let mut logic = played_game(10);
+        logic.speed_effect = Some((f32::NAN, 100));
+
+        let error = SnakeGameLogic::from_bytes(&logic.to_bytes().unwrap()).unwrap_err();
+        assert_eq!(error.to_string(), "the save has an invalid speed");
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+// eating again within this many ticks of the previous food keeps the combo going
+const COMBO_WINDOW_TICKS: u64 = 25;
+const MAX_MULTIPLIER: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Score {
+    points: u64,
+    combo: u32,
+    best_combo: u32,
+    last_food_tick: Option<u64>,
+}
+
+impl Score {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    pub fn combo(&self) -> u32 {
+        self.combo
+    }
+
+    pub fn best_combo(&self) -> u32 {
+        self.best_combo
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        self.combo.clamp(1, MAX_MULTIPLIER)
+    }
+
+    // drops the combo once its window has passed without food
+    pub fn update(&mut self, tick: u64) {
+        if self.last_food_tick.is_some_and(|last| tick - last > COMBO_WINDOW_TICKS) {
+            self.combo = 0;
+        }
+    }
+
+    pub fn award(&mut self, base_points: u32, tick: u64, speed_level: u32) -> u64 {
+        self.update(tick);
+
+        self.combo += 1;
+        self.best_combo = self.best_combo.max(self.combo);
+        self.last_food_tick = Some(tick);
+
+        let gained = base_points as u64 * self.multiplier() as u64 * speed_level.max(1) as u64;
+        self.points += gained;
+        gained
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_meals_build_a_combo_that_lapses_after_the_window() {
+        let mut score = Score::new();
+        assert_eq!(score.award(10, 1, 1), 10);
+        assert_eq!(score.award(10, 5, 1), 20);
+        assert_eq!(score.award(10, 5 + COMBO_WINDOW_TICKS, 2), 60);
+        assert_eq!(score.multiplier(), 3);
+
+        score.update(6 + 2 * COMBO_WINDOW_TICKS);
+        assert_eq!(score.combo(), 0);
+        assert_eq!(score.multiplier(), 1);
+        assert_eq!(score.award(10, 6 + 2 * COMBO_WINDOW_TICKS, 1), 10);
+        assert_eq!(score.best_combo(), 3);
+        assert_eq!(score.points(), 100);
+    }
+
+    #[test]
+    fn the_multiplier_is_capped() {
+        let mut score = Score::new();
+        for tick in 0..20 {
+            score.award(1, tick, 1);
+        }
+        assert_eq!(score.combo(), 20);
+        assert_eq!(score.multiplier(), MAX_MULTIPLIER);
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+const MAX_TICKS_PER_SECOND: f32 = 30.0;
+const TICKS_PER_SECOND_PER_LEVEL: f32 = 5.0;
+
+// maps the snake's length to how many logic ticks run per second
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpeedCurve {
+    Constant {
+        ticks_per_second: f32,
+    },
+    Linear {
+        base: f32,
+        per_segment: f32,
+        max: f32,
+    },
+    // one entry per level, advancing a level every `segments_per_level` segments
+    Stepped {
+        segments_per_level: usize,
+        levels: Vec<f32>,
+    },
+    // (length, ticks per second) control points, interpolated linearly and clamped at both ends
+    Custom(Vec<(usize, f32)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Default for SpeedCurve {
+    fn default() -> Self {
+        Difficulty::default().speed_curve()
+    }
+}
+
+impl SpeedCurve {
+    // the normal growth rate, starting from a custom speed
+    pub fn growing_from(base: f32) -> Self {
+        SpeedCurve::Linear { base, per_segment: 0.1, max: MAX_TICKS_PER_SECOND }
+    }
+
+    pub fn ticks_per_second(&self, snake_length: usize) -> f32 {
+        let ticks_per_second = match self {
+            SpeedCurve::Constant { ticks_per_second } => *ticks_per_second,
+            SpeedCurve::Linear { base, per_segment, max } => (base + snake_length as f32 * per_segment).min(*max),
+            SpeedCurve::Stepped { segments_per_level, levels } => {
+                let level = (snake_length / (*segments_per_level).max(1)).min(levels.len().saturating_sub(1));
+                levels.get(level).copied().unwrap_or(1.0)
+            }
+            SpeedCurve::Custom(points) => Self::interpolate(points, snake_length),
+        };
+
+        ticks_per_second.clamp(f32::EPSILON, MAX_TICKS_PER_SECOND)
+    }
+
+    // a curve with a NaN or infinite number in it cannot be turned into a tick interval
+    pub fn is_finite(&self) -> bool {
+        match self {
+            SpeedCurve::Constant { ticks_per_second } => ticks_per_second.is_finite(),
+            SpeedCurve::Linear { base, per_segment, max } => [base, per_segment, max].iter().all(|value| value.is_finite()),
+            SpeedCurve::Stepped { levels, .. } => levels.iter().all(|value| value.is_finite()),
+            SpeedCurve::Custom(points) => points.iter().all(|(_, value)| value.is_finite()),
+        }
+    }
+
+    // 1 at the normal starting speed, one more for every further 5 ticks per second
+    pub fn level(&self, snake_length: usize) -> u32 {
+        ((self.ticks_per_second(snake_length) / TICKS_PER_SECOND_PER_LEVEL) as u32).max(1)
+    }
+
+    fn interpolate(points: &[(usize, f32)], snake_length: usize) -> f32 {
+        let Some(&(first_length, first_speed)) = points.first() else {
+            return 1.0;
+        };
+        if snake_length <= first_length {
+            return first_speed;
+        }
+
+        for window in points.windows(2) {
+            let ((from_length, from_speed), (to_length, to_speed)) = (window[0], window[1]);
+            if snake_length <= to_length {
+                let t = (snake_length - from_length) as f32 / (to_length - from_length).max(1) as f32;
+                return from_speed + (to_speed - from_speed) * t;
+            }
+        }
+
+        points[points.len() - 1].1
+    }
+}
+
+impl Difficulty {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            "insane" => Some(Difficulty::Insane),
+            _ => None,
+        }
+    }
+
+    pub fn speed_curve(&self) -> SpeedCurve {
+        match self {
+            Difficulty::Easy => SpeedCurve::Linear { base: 4.0, per_segment: 0.05, max: 12.0 },
+            Difficulty::Normal => SpeedCurve::growing_from(5.0),
+            Difficulty::Hard => SpeedCurve::Linear { base: 8.0, per_segment: 0.15, max: 30.0 },
+            Difficulty::Insane => SpeedCurve::Stepped {
+                segments_per_level: 5,
+                levels: vec![12.0, 15.0, 18.0, 22.0, 26.0, 30.0],
+            },
+        }
+    }
+}
@@ -1,25 +1,71 @@
-const TICKS_PER_SECOND: usize = 5;
+use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
+
+const MAX_ACCUMULATED: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LogicTimer {
-    last_update: std::time::Instant,
+    accumulator: Duration,
 }
 
 impl LogicTimer {
     pub fn new() -> Self {
         Self {
-            last_update: std::time::Instant::now(),
+            accumulator: Duration::ZERO,
         }
     }
 
-    pub fn should_update(&mut self, snake_len: usize) -> bool {
-        let now = std::time::Instant::now();
-        let elapsed = now - self.last_update;
+    // the backlog is capped so a long stall doesn't turn into a burst of ticks, but never below one
+    // interval, or a slow enough speed would never tick at all
+    pub fn accumulate(&mut self, elapsed: Duration, ticks_per_second: f32) {
+        let cap = MAX_ACCUMULATED.max(Self::interval(ticks_per_second));
+        self.accumulator = (self.accumulator + elapsed).min(cap);
+    }
 
-        if elapsed.as_secs_f32() > 1.0 / (TICKS_PER_SECOND as f32 + snake_len as f32 / 10.0).min(30.0) {
-            self.last_update = now;
+    pub fn try_tick(&mut self, ticks_per_second: f32) -> bool {
+        let interval = Self::interval(ticks_per_second);
+
+        if self.accumulator >= interval {
+            self.accumulator -= interval;
             true
         } else {
             false
         }
     }
-}
\ No newline at end of file
+
+    fn interval(ticks_per_second: f32) -> Duration {
+        Duration::from_secs_f32(1.0 / ticks_per_second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_at_speeds_slower_than_the_cap() {
+        let mut timer = LogicTimer::new();
+        let mut ticks = 0;
+        for _ in 0..100 {
+            timer.accumulate(Duration::from_millis(16), 2.0);
+            while timer.try_tick(2.0) {
+                ticks += 1;
+            }
+        }
+
+        assert_eq!(ticks, 3);
+    }
+
+    #[test]
+    fn a_long_stall_runs_a_bounded_number_of_ticks() {
+        let mut timer = LogicTimer::new();
+        timer.accumulate(Duration::from_secs(10), 8.0);
+
+        let mut ticks = 0;
+        while timer.try_tick(8.0) {
+            ticks += 1;
+        }
+        assert_eq!(ticks, 2);
+    }
+}
@@ -1,8 +1,34 @@
+use wgpu_snake_game::{args::Args, logic::Replay};
+
 mod application;
 
 fn main() -> Result<(), anyhow::Error> {
     let event_loop = winit::event_loop::EventLoop::new()?;
-    let mut snake_game = application::SnakeGame::new(30, 20);
+    let seed = rand::random();
+    println!("seed: {}", seed);
+
+    let args = Args::from_env();
+    let config = args.game_config()?;
+
+    let save_path = args.value("--save")?.unwrap_or("snake-save.json").into();
+    let mut snake_game = application::SnakeGame::new(config, seed, save_path);
+    if let Some(players) = args.players()? {
+        snake_game.set_players(&players)?;
+    }
+    if let Some(path) = args.value("--record")? {
+        snake_game.record(path.into(), seed);
+    }
+    if let Some(path) = args.value("--replay")? {
+        let replay = Replay::load(path)?;
+        println!("replay: seed {}, {} ticks", replay.seed, replay.ticks());
+        snake_game.play_replay(replay);
+    }
+    if let Some(name) = args.value("--autopilot")? {
+        snake_game.toggle_autopilot(name)?;
+    }
+    if args.flag("--resume") {
+        snake_game.load()?;
+    }
     
     event_loop.run_app(&mut snake_game)?;
 
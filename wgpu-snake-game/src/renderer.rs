@@ -8,7 +8,7 @@ use wgpu::*;
 use window::{Window, WindowAttributes, WindowId};
 use winit::*;
 
-use crate::logic::SnakeGameLogic;
+use crate::logic::{GamePhase, PlayerSnake, SnakeGameLogic};
 
 mod background;
 mod cube;
@@ -22,6 +22,7 @@ pub struct SnakeGameRenderer {
     queue: Queue,
     background: background::BackgroundRenderer,
     cube: cube::CubeRenderer,
+    title: String,
 }
 
 impl SnakeGameRenderer {
@@ -84,6 +85,7 @@ impl SnakeGameRenderer {
                 queue,
                 background,
                 cube,
+                title: String::new(),
             }
         )
     }
@@ -94,7 +96,30 @@ impl SnakeGameRenderer {
         self.surface.configure(&self.device, &self.surface_configuration);
     }
 
-    fn window_redraw(&mut self, logic: &SnakeGameLogic) -> Result<()> {
+    fn update_title(&mut self, logic: &SnakeGameLogic, phase: GamePhase) {
+        let title = match (phase, logic.snakes()) {
+            (GamePhase::Title, _) => "snake game - press enter to start".to_string(),
+            (GamePhase::Playing, [snake]) => format!("snake game - score {} (x{})", snake.score().points(), snake.score().multiplier()),
+            (GamePhase::Paused, [snake]) => format!("snake game - score {} - paused (press p to resume)", snake.score().points()),
+            (GamePhase::GameOver(outcome), [snake]) => format!(
+                "snake game - {} - score {}, best combo {} (press enter to restart)",
+                outcome,
+                snake.score().points(),
+                snake.score().best_combo(),
+            ),
+            (GamePhase::Playing, snakes) => format!("snake game - {}", scores(snakes)),
+            (GamePhase::Paused, snakes) => format!("snake game - {} - paused (press p to resume)", scores(snakes)),
+            (GamePhase::GameOver(outcome), snakes) => format!("snake game - {} - {} (press enter to restart)", outcome, scores(snakes)),
+        };
+
+        if self.title != title {
+            self.window.set_title(&title);
+            self.title = title;
+        }
+    }
+
+    fn window_redraw(&mut self, logic: &SnakeGameLogic, phase: GamePhase) -> Result<()> {
+        self.update_title(logic, phase);
 
         let surface_texture = self.surface.get_current_texture()?;
 
@@ -118,7 +143,7 @@ impl SnakeGameRenderer {
             ..Default::default()
         });
 
-        self.background.draw(&self.window, logic, &self.queue, &self.device, &mut render_pass);
+        self.background.draw(&self.window, logic, phase, &self.queue, &self.device, &mut render_pass);
         self.cube.draw(&self.window, logic, &self.queue, &self.device, &mut render_pass);
 
         drop(render_pass);
@@ -134,7 +159,7 @@ impl SnakeGameRenderer {
         self.window.request_redraw();
     }
 
-    pub fn window_event(&mut self, window_id: &WindowId, event: &WindowEvent, logic: &SnakeGameLogic) -> Result<()> {
+    pub fn window_event(&mut self, window_id: &WindowId, event: &WindowEvent, logic: &SnakeGameLogic, phase: GamePhase) -> Result<()> {
         if window_id != &self.window.id() {
             return Ok(());
         }
@@ -144,8 +169,18 @@ impl SnakeGameRenderer {
                 self.window_resize(size);
                 Ok(())
             },
-            WindowEvent::RedrawRequested => self.window_redraw(logic),
+            WindowEvent::RedrawRequested => self.window_redraw(logic, phase),
             _ => Ok(()),
         }
     }
+}
+
+// every player's score, for the title with several snakes
+fn scores(snakes: &[PlayerSnake]) -> String {
+    snakes
+        .iter()
+        .enumerate()
+        .map(|(index, snake)| format!("player {} {}", index + 1, snake.score().points()))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
\ No newline at end of file
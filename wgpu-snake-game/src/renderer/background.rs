@@ -3,28 +3,52 @@ use util::{BufferInitDescriptor, DeviceExt};
 use wgpu::*;
 use winit::window::Window;
 
-use crate::logic::SnakeGameLogic;
+use crate::logic::{GamePhase, SnakeGameLogic};
 
 mod buffer {
     use winit::window::Window;
 
-    use crate::{logic::SnakeGameLogic, renderer::BLOCK_SIZE};
+    use crate::{logic::{EdgePolicy, GameOutcome, GamePhase, SnakeGameLogic}, renderer::BLOCK_SIZE};
 
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
     pub struct BackgroundBuffer {
+        color: [f32; 4],
         width_percentage: f32,
         height_percentage: f32,
+        border_x: f32,
+        border_y: f32,
     }
 
-    impl From<(&SnakeGameLogic, &Window)> for BackgroundBuffer {
-        fn from((logic, window): (&SnakeGameLogic, &Window)) -> Self {
-            let width_percentage = (logic.width() as f32 + 1.0) * BLOCK_SIZE / window.inner_size().width as f32;
-            let height_percentage = (logic.height() as f32 + 1.0) * BLOCK_SIZE / window.inner_size().height as f32;
+    impl From<(&SnakeGameLogic, GamePhase, &Window)> for BackgroundBuffer {
+        fn from((logic, phase, window): (&SnakeGameLogic, GamePhase, &Window)) -> Self {
+            let board = logic.board();
+            let width_percentage = board.width() as f32 * BLOCK_SIZE / window.inner_size().width as f32;
+            let height_percentage = board.height() as f32 * BLOCK_SIZE / window.inner_size().height as f32;
+
+            let color = match phase {
+                GamePhase::Title => [0.05, 0.05, 0.2, 1.0],
+                GamePhase::Playing => [0.0, 0.0, 0.0, 1.0],
+                GamePhase::Paused => [0.2, 0.2, 0.2, 1.0],
+                GamePhase::GameOver(GameOutcome::Won | GameOutcome::PlayerWon(_)) => [0.3, 0.25, 0.0, 1.0],
+                GamePhase::GameOver(GameOutcome::Died(_) | GameOutcome::Draw) => [0.3, 0.0, 0.0, 1.0],
+            };
+
+            // obstacles are not painted here: the cube renderer draws them as blocks on the same grid as
+            // the snake and food, which would cover anything drawn underneath
+
+            // solid walls get a quarter block thick frame, wrap-around edges stay open
+            let (border_x, border_y) = match logic.edge_policy() {
+                EdgePolicy::Walls => (0.5 / board.width() as f32, 0.5 / board.height() as f32),
+                EdgePolicy::Wrap => (0.0, 0.0),
+            };
 
             Self {
+                color,
                 width_percentage,
                 height_percentage,
+                border_x,
+                border_y,
             }
         }
     }
@@ -44,7 +68,7 @@ impl BackgroundRenderer {
         logic: &SnakeGameLogic,
         window: &Window,
     ) -> Result<Self> {
-        let background = buffer::BackgroundBuffer::from((logic, window));
+        let background = buffer::BackgroundBuffer::from((logic, GamePhase::Title, window));
 
         let background_buffer = device.create_buffer_init(
             &BufferInitDescriptor {
@@ -59,7 +83,7 @@ impl BackgroundRenderer {
             entries: &[
                 BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: ShaderStages::VERTEX,
+                    visibility: ShaderStages::VERTEX_FRAGMENT,
                     ty: BindingType::Buffer {
                         ty: BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -134,16 +158,17 @@ impl BackgroundRenderer {
         &mut self,
         window: &Window,
         logic: &SnakeGameLogic,
+        phase: GamePhase,
         queue: &Queue,
         _device: &Device,
         render_pass: &mut RenderPass
     ) {
-        let background = buffer::BackgroundBuffer::from((logic, window));
+        let background = buffer::BackgroundBuffer::from((logic, phase, window));
         if self.background != background {
             queue.write_buffer(
                 &self.background_buffer,
                 0,
-                bytemuck::cast_slice(&[self.background]),
+                bytemuck::cast_slice(&[background]),
             );
             self.background = background;
         }
@@ -1,6 +1,14 @@
 struct Background {
+    color: vec4<f32>,
     width_percentage: f32,
     height_percentage: f32,
+    border_x: f32,
+    border_y: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) local_position: vec2<f32>
 };
 
 @group(0) @binding(0)
@@ -9,7 +17,7 @@ var<uniform> background: Background;
 @vertex
 fn vs_main(
     @builtin(vertex_index) vertex_index: u32,
-) -> @builtin(position) vec4<f32> {
+) -> VertexOutput {
     var positions = array<vec2<f32>, 6>(
         vec2<f32>(-1.0, -1.0),
         vec2<f32>(1.0, 1.0),
@@ -19,17 +27,24 @@ fn vs_main(
         vec2<f32>(1.0, 1.0)
     );
 
-    return vec4<f32>(
-        positions[vertex_index].x * background.width_percentage,
-        positions[vertex_index].y * background.height_percentage,
-        0.0,
-        1.0
+    return VertexOutput(
+        vec4<f32>(
+            positions[vertex_index].x * background.width_percentage,
+            positions[vertex_index].y * background.height_percentage,
+            0.0,
+            1.0
+        ),
+        positions[vertex_index]
     );
 }
 
 @fragment
 fn fs_main(
-    @builtin(position) vertex_position: vec4<f32>
+    vertex_output: VertexOutput
 ) -> @location(0) vec4<f32> {
-    return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    if abs(vertex_output.local_position.x) > 1.0 - background.border_x || abs(vertex_output.local_position.y) > 1.0 - background.border_y {
+        return vec4<f32>(0.5, 0.5, 0.5, 1.0);
+    }
+
+    return background.color;
 }
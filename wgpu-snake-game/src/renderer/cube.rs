@@ -8,7 +8,7 @@ use crate::logic::SnakeGameLogic;
 mod buffer {
     use winit::window::Window;
 
-    use crate::{logic::SnakeGameLogic, renderer::BLOCK_SIZE};
+    use crate::{logic::{Board, FoodKind, Position, SnakeGameLogic}, renderer::BLOCK_SIZE};
 
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
@@ -26,6 +26,34 @@ mod buffer {
         }
     }
 
+    // cells are laid out around the middle of the board so it stays centred in the window
+    fn screen_position(board: &Board, position: &Position, window: &Window) -> [f32; 2] {
+        let [x, y] = board.offset_from_centre(position);
+        [
+            x * BLOCK_SIZE / window.inner_size().width as f32 * 2.0,
+            y * BLOCK_SIZE / window.inner_size().height as f32 * 2.0,
+        ]
+    }
+
+    // body and head colour for each snake, reused from the start when there are more snakes
+    const SNAKE_PALETTES: [([f32; 4], [f32; 4]); 4] = [
+        ([0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]),
+        ([1.0, 0.45, 0.7, 1.0], [0.65, 0.05, 0.35, 1.0]),
+        ([0.45, 0.75, 1.0, 1.0], [0.05, 0.25, 0.6, 1.0]),
+        ([0.65, 0.45, 0.25, 1.0], [0.3, 0.15, 0.05, 1.0]),
+    ];
+
+    fn food_color(kind: FoodKind) -> [f32; 4] {
+        match kind {
+            FoodKind::Regular => [1.0, 0.0, 0.0, 1.0],
+            FoodKind::Golden => [1.0, 0.84, 0.0, 1.0],
+            FoodKind::Shrinking => [0.0, 1.0, 1.0, 1.0],
+            FoodKind::SpeedUp => [1.0, 0.5, 0.0, 1.0],
+            FoodKind::SlowDown => [0.8, 0.8, 1.0, 1.0],
+            FoodKind::Poison => [0.6, 0.0, 0.8, 1.0],
+        }
+    }
+
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
     pub struct CubeInstanceBuffer {
@@ -35,37 +63,41 @@ mod buffer {
 
     impl CubeInstanceBuffer {
         pub fn from(logic: &SnakeGameLogic, window: &Window) -> Vec<Self> {
-            let mut instances = Vec::with_capacity(logic.player_snake.body().len() + 1 + logic.food.positions().len());
+            let board = logic.board();
+            let snake_cells: usize = logic.snakes().iter().map(|snake| snake.length()).sum();
+            let mut instances = Vec::with_capacity(logic.obstacles().len() + snake_cells + logic.food.items().len());
 
-            // add snake
-            for snake in logic.player_snake.body().iter() {
+            // add obstacles
+            for obstacle in logic.obstacles().iter() {
                 instances.push(Self {
-                    position: [
-                        snake.x() as f32 * BLOCK_SIZE / window.inner_size().width as f32 * 2.0,
-                        snake.y() as f32 * BLOCK_SIZE / window.inner_size().height as f32 * 2.0,
-                    ],
-                    color: [0.0, 1.0, 0.0, 1.0],
+                    position: screen_position(board, obstacle, window),
+                    color: [0.5, 0.5, 0.5, 1.0],
                 });
             }
 
-            // add snake head
-            let head = logic.player_snake.head();
-            instances.push(Self {
-                position: [
-                    head.x() as f32 * BLOCK_SIZE / window.inner_size().width as f32 * 2.0,
-                    head.y() as f32 * BLOCK_SIZE / window.inner_size().height as f32 * 2.0,
-                ],
-                color: [0.0, 0.0, 1.0, 1.0],
-            });
+            // add snakes. one that is out of a game with several has left the board, its cells are free
+            // for food and the others, while a lone snake stays where it crashed
+            let lone = logic.snakes().len() == 1;
+            for (index, snake) in logic.snakes().iter().enumerate().filter(|(_, snake)| lone || snake.is_alive()) {
+                let (body_color, head_color) = SNAKE_PALETTES[index % SNAKE_PALETTES.len()];
+                for segment in snake.body().iter() {
+                    instances.push(Self {
+                        position: screen_position(board, segment, window),
+                        color: body_color,
+                    });
+                }
+
+                instances.push(Self {
+                    position: screen_position(board, snake.head(), window),
+                    color: head_color,
+                });
+            }
 
             // add foods
-            for food in logic.food.positions().iter() {
+            for food in logic.food.items().iter() {
                 instances.push(Self {
-                    position: [
-                        food.x() as f32 * BLOCK_SIZE / window.inner_size().width as f32 * 2.0,
-                        food.y() as f32 * BLOCK_SIZE / window.inner_size().height as f32 * 2.0,
-                    ],
-                    color: [1.0, 0.0, 0.0, 1.0],
+                    position: screen_position(board, &food.position, window),
+                    color: food_color(food.kind),
                 });
             }
 